@@ -1,4 +1,4 @@
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use librscrc::prelude::*;
 
 fn bench_crc(c: &mut Criterion) {
@@ -34,6 +34,7 @@ fn bench_crc(c: &mut Criterion) {
         },
     );
 
+    #[cfg(feature = "hardware")]
     group.bench_with_input(
         BenchmarkId::new("crc32c_hardware", big.len() as u64),
         &big,
@@ -46,6 +47,7 @@ fn bench_crc(c: &mut Criterion) {
         },
     );
 
+    #[cfg(feature = "hardware")]
     group.bench_with_input(
         BenchmarkId::new("crc32c_simd", big.len() as u64),
         &big,
@@ -58,6 +60,7 @@ fn bench_crc(c: &mut Criterion) {
         },
     );
 
+    #[cfg(feature = "hardware")]
     group.bench_with_input(
         BenchmarkId::new("crc32_simd", big.len() as u64),
         &big,
@@ -70,6 +73,7 @@ fn bench_crc(c: &mut Criterion) {
         },
     );
 
+    #[cfg(feature = "hardware")]
     group.bench_with_input(
         BenchmarkId::new("custom_crc32_simd", big.len() as u64),
         &big,
@@ -1,5 +1,5 @@
 use crc64fast::Digest;
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use librscrc::prelude::*;
 
 fn bench_crc(c: &mut Criterion) {
@@ -23,16 +23,43 @@ fn bench_crc(c: &mut Criterion) {
         },
     );
 
+    group.bench_with_input(
+        BenchmarkId::new("crc64ecma_lookup", big.len() as u64),
+        &big,
+        |b, data| {
+            b.iter(|| {
+                let mut crc = Crc64ECMA::new_lookup();
+                crc.update(*data);
+                crc.digest()
+            })
+        },
+    );
+
+    #[cfg(feature = "hardware")]
+    group.bench_with_input(
+        BenchmarkId::new("crc64ecma_simd", big.len() as u64),
+        &big,
+        |b, data| {
+            b.iter(|| {
+                let mut crc = Crc64ECMA::new_simd();
+                crc.update(*data);
+                crc.digest()
+            })
+        },
+    );
+
     group.bench_with_input(
         BenchmarkId::new("crc64ecmafast", big.len() as u64),
         &big,
-        |b, data| b.iter(|| {
-            let mut crc = Digest::new();
-            crc.write(*data);
-            crc.sum64()
-        }),
+        |b, data| {
+            b.iter(|| {
+                let mut crc = Digest::new();
+                crc.write(*data);
+                crc.sum64()
+            })
+        },
     );
 }
 
 criterion_group!(benches, bench_crc);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
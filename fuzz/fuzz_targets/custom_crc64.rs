@@ -0,0 +1,20 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use librscrc::prelude::*;
+use std::convert::TryInto;
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() >= 8 {
+        let polynomial = u64::from_le_bytes(data[..8].try_into().unwrap());
+        let data = &data[8..];
+        let mut naive = CustomCrc64::new_naive(polynomial);
+        let mut lookup = CustomCrc64::new_lookup(polynomial);
+        let mut simd = CustomCrc64::new_simd(polynomial);
+        naive.update(data);
+        lookup.update(data);
+        simd.update(data);
+        let naive_result = naive.digest();
+        assert_eq!(naive_result, lookup.digest());
+        assert_eq!(naive_result, simd.digest());
+    }
+});
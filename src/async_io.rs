@@ -0,0 +1,283 @@
+//! Async adapters computing a crc over data passing through a reader or writer
+//!
+//! [AsyncCrcReader] and [AsyncCrcWriter] are the async counterparts of [crate::io::CrcReader] and
+//! [crate::io::CrcWriter]. They implement the `tokio` io traits when the "tokio" feature is enabled
+//! and the `futures-io` traits when the "futures-io" feature is enabled. The wrapped reader or
+//! writer has to be [Unpin], pin it with [Box::pin] otherwise.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::crc::CrcDigest;
+
+/// Async reader adapter which updates a crc with all the data read from the inner reader
+#[derive(Debug)]
+pub struct AsyncCrcReader<R, D> {
+    inner: R,
+    crc: D,
+}
+
+impl<R, D> AsyncCrcReader<R, D> {
+    /// Wraps `inner`, updating `crc` with the data read from it
+    pub fn new(inner: R, crc: D) -> Self {
+        Self { inner, crc }
+    }
+
+    /// Returns the crc of the data read so far
+    pub fn crc(&self) -> &D {
+        &self.crc
+    }
+
+    /// Returns the crc of the data read so far mutably
+    pub fn crc_mut(&mut self) -> &mut D {
+        &mut self.crc
+    }
+
+    /// Returns a reference to the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner reader. Data read directly from it is not included
+    /// in the crc.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Consumes the adapter, returning the inner reader and the crc
+    pub fn into_parts(self) -> (R, D) {
+        (self.inner, self.crc)
+    }
+}
+
+/// Async writer adapter which updates a crc with all the data written to the inner writer
+#[derive(Debug)]
+pub struct AsyncCrcWriter<W, D> {
+    inner: W,
+    crc: D,
+}
+
+impl<W, D> AsyncCrcWriter<W, D> {
+    /// Wraps `inner`, updating `crc` with the data written to it
+    pub fn new(inner: W, crc: D) -> Self {
+        Self { inner, crc }
+    }
+
+    /// Returns the crc of the data written so far
+    pub fn crc(&self) -> &D {
+        &self.crc
+    }
+
+    /// Returns the crc of the data written so far mutably
+    pub fn crc_mut(&mut self) -> &mut D {
+        &mut self.crc
+    }
+
+    /// Returns a reference to the inner writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer. Data written directly to it is not
+    /// included in the crc.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Consumes the adapter, returning the inner writer and the crc
+    pub fn into_parts(self) -> (W, D) {
+        (self.inner, self.crc)
+    }
+}
+
+/// Updates `crc` with the bytes produced or accepted by a poll of the inner reader or writer
+fn update<D: CrcDigest>(
+    crc: &mut D,
+    poll: Poll<io::Result<usize>>,
+    buf: &[u8],
+) -> Poll<io::Result<usize>> {
+    match poll {
+        Poll::Ready(Ok(len)) => {
+            crc.update(&buf[..len]);
+            Poll::Ready(Ok(len))
+        }
+        poll => poll,
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin, D: CrcDigest + Unpin> tokio::io::AsyncRead
+    for AsyncCrcReader<R, D>
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled = buf.filled().len();
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = poll {
+            this.crc.update(&buf.filled()[filled..]);
+        }
+        poll
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin, D: CrcDigest + Unpin> tokio::io::AsyncWrite
+    for AsyncCrcWriter<W, D>
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+        update(&mut this.crc, poll, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: futures_io::AsyncRead + Unpin, D: CrcDigest + Unpin> futures_io::AsyncRead
+    for AsyncCrcReader<R, D>
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        update(&mut this.crc, poll, buf)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<W: futures_io::AsyncWrite + Unpin, D: CrcDigest + Unpin> futures_io::AsyncWrite
+    for AsyncCrcWriter<W, D>
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+        update(&mut this.crc, poll, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check32::Crc32C;
+    use crate::check64::Crc64ECMA;
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    const DATA: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    struct NoopWake;
+
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// Polls `f` until it is ready. The readers and writers used in the tests never return
+    /// pending, so no waker is needed.
+    fn ready<T>(mut f: impl FnMut(&mut Context<'_>) -> Poll<T>) -> T {
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(value) = f(&mut cx) {
+                return value;
+            }
+        }
+    }
+
+    fn crc64_ecma(data: &[u8]) -> u64 {
+        let mut crc = Crc64ECMA::new_lookup();
+        crc.update(data);
+        crc.digest()
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_tokio_adapters() {
+        use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+        let mut reader = AsyncCrcReader::new(DATA, Crc32C::new_lookup());
+        let mut data = [0u8; 64];
+        let mut read = 0;
+        loop {
+            // read in small chunks to update the crc several times
+            let mut buf = ReadBuf::new(&mut data[read..(read + 5).min(64)]);
+            ready(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf)).unwrap();
+            if buf.filled().is_empty() {
+                break;
+            }
+            read += buf.filled().len();
+        }
+        assert_eq!(&data[..read], DATA);
+        assert_eq!(reader.crc().digest(), 0x22620404);
+
+        let mut writer = AsyncCrcWriter::new(Vec::new(), Crc64ECMA::new_lookup());
+        for chunk in DATA.chunks(7) {
+            let written = ready(|cx| Pin::new(&mut writer).poll_write(cx, chunk)).unwrap();
+            assert_eq!(written, chunk.len());
+        }
+        ready(|cx| Pin::new(&mut writer).poll_shutdown(cx)).unwrap();
+        let (data, crc) = writer.into_parts();
+        assert_eq!(data, DATA);
+        assert_eq!(crc.digest(), crc64_ecma(DATA));
+    }
+
+    #[cfg(feature = "futures-io")]
+    #[test]
+    fn test_futures_io_adapters() {
+        use futures_io::{AsyncRead, AsyncWrite};
+
+        let mut reader = AsyncCrcReader::new(DATA, Crc64ECMA::new_lookup());
+        let mut data = [0u8; 64];
+        let mut read = 0;
+        loop {
+            let buf = &mut data[read..(read + 5).min(64)];
+            let len = ready(|cx| Pin::new(&mut reader).poll_read(cx, buf)).unwrap();
+            if len == 0 {
+                break;
+            }
+            read += len;
+        }
+        assert_eq!(&data[..read], DATA);
+        assert_eq!(reader.crc().digest(), crc64_ecma(DATA));
+
+        let mut writer = AsyncCrcWriter::new(Vec::new(), Crc32C::new_lookup());
+        for chunk in DATA.chunks(7) {
+            let written = ready(|cx| Pin::new(&mut writer).poll_write(cx, chunk)).unwrap();
+            assert_eq!(written, chunk.len());
+        }
+        ready(|cx| Pin::new(&mut writer).poll_close(cx)).unwrap();
+        let (data, crc) = writer.into_parts();
+        assert_eq!(data, DATA);
+        assert_eq!(crc.digest(), 0x22620404);
+    }
+}
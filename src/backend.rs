@@ -0,0 +1,56 @@
+use core::fmt;
+
+/// The approach used by a crc instance to process data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// Processes data one bit at a time
+    Naive,
+    /// Processes data using slice by 16 lookup tables
+    Lookup,
+    /// Processes data using the cpu's crc instructions
+    Hardware,
+    /// Folds 128 bit registers using sse and pclmulqdq on x86 and x86_64
+    SimdSse,
+    /// Folds 256 bit registers using avx2 and vpclmulqdq on x86_64
+    SimdAvx2,
+    /// Folds 512 bit registers using avx512f and vpclmulqdq on x86_64
+    SimdAvx512,
+    /// Folds 128 bit registers using neon and aes on aarch64
+    SimdNeon,
+}
+
+impl Backend {
+    /// All backends, from slowest to fastest
+    pub const ALL: [Backend; 7] = [
+        Backend::Naive,
+        Backend::Lookup,
+        Backend::Hardware,
+        Backend::SimdSse,
+        Backend::SimdNeon,
+        Backend::SimdAvx2,
+        Backend::SimdAvx512,
+    ];
+
+    /// Simd backends in the order they are preferred by `new_simd`
+    #[cfg(feature = "hardware")]
+    pub(crate) const SIMD: [Backend; 4] = [
+        Backend::SimdAvx512,
+        Backend::SimdAvx2,
+        Backend::SimdSse,
+        Backend::SimdNeon,
+    ];
+}
+
+/// Error returned by `new_with_backend` when the requested backend is not supported by the
+/// algorithm, the enabled crate features or the cpu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedBackend(pub Backend);
+
+impl fmt::Display for UnsupportedBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "crc backend {:?} is not supported", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnsupportedBackend {}
@@ -0,0 +1,307 @@
+//! Named crc algorithms from the [reveng catalogue](https://reveng.sourceforge.io/crc-catalogue/).
+//!
+//! Every algorithm is a [CrcParams] constant which can be passed to the `*with_params`
+//! constructors of [Crc](crate::crc::Crc), or looked up by its catalogue name or one of its
+//! aliases with [lookup].
+//!
+//! ```
+//! use librscrc::catalogue::{self, CRC_32_ISCSI};
+//! use librscrc::prelude::*;
+//!
+//! let mut crc = CustomCrc32::with_params(CRC_32_ISCSI);
+//! crc.update(b"123456789");
+//! assert_eq!(crc.digest(), 0xE3069283);
+//!
+//! let params = catalogue::lookup("crc-16/modbus").unwrap();
+//! let mut crc = CustomCrc16::with_params(params);
+//! crc.update(b"123456789");
+//! assert_eq!(crc.digest() as u64, params.check);
+//! ```
+
+use crate::params::CrcParams;
+
+/// A named entry of the catalogue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcAlgorithm {
+    /// Name of the algorithm in the reveng catalogue, e.g. `CRC-32/ISCSI`
+    pub name: &'static str,
+    /// Other names the algorithm is known by, e.g. `CRC-32C`
+    pub aliases: &'static [&'static str],
+    /// Parameters of the algorithm
+    pub params: CrcParams,
+}
+
+impl CrcAlgorithm {
+    /// Returns whether `name` is the name or one of the aliases of the algorithm, ignoring
+    /// ascii case
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Returns the algorithm with the given name or alias, ignoring ascii case
+pub fn find(name: &str) -> Option<&'static CrcAlgorithm> {
+    ALGORITHMS.iter().find(|algorithm| algorithm.is_named(name))
+}
+
+/// Returns the parameters of the algorithm with the given name or alias, ignoring ascii case
+pub fn lookup(name: &str) -> Option<CrcParams> {
+    find(name).map(|algorithm| algorithm.params)
+}
+
+macro_rules! catalogue {
+    ($(
+        $(#[$attr:meta])*
+        $const:ident($name:literal $(, $alias:literal)*) = {
+            $width:literal, $poly:literal, $init:literal, $refin:literal, $refout:literal,
+            $xorout:literal, $check:literal, $residue:literal $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$attr])*
+            #[doc = concat!("`", $name, "`", $(", alias `", $alias, "`",)*)]
+            pub const $const: CrcParams = CrcParams {
+                width: $width,
+                poly: $poly,
+                init: $init,
+                refin: $refin,
+                refout: $refout,
+                xorout: $xorout,
+                check: $check,
+                residue: $residue,
+            };
+        )*
+
+        /// All the algorithms of the catalogue, ordered by width
+        pub const ALGORITHMS: &[CrcAlgorithm] = &[
+            $(CrcAlgorithm {
+                name: $name,
+                aliases: &[$($alias),*],
+                params: $const,
+            }),*
+        ];
+    };
+}
+
+catalogue! {
+    CRC_8_AUTOSAR("CRC-8/AUTOSAR") = {
+        8, 0x2F, 0xFF, false, false, 0xFF, 0xDF, 0x42,
+    }
+    CRC_8_CDMA2000("CRC-8/CDMA2000") = {
+        8, 0x9B, 0xFF, false, false, 0x00, 0xDA, 0x00,
+    }
+    CRC_8_I_432_1("CRC-8/I-432-1", "CRC-8/ITU") = {
+        8, 0x07, 0x00, false, false, 0x55, 0xA1, 0xAC,
+    }
+    CRC_8_MAXIM_DOW("CRC-8/MAXIM-DOW", "CRC-8/MAXIM", "DOW-CRC") = {
+        8, 0x31, 0x00, true, true, 0x00, 0xA1, 0x00,
+    }
+    CRC_8_ROHC("CRC-8/ROHC") = {
+        8, 0x07, 0xFF, true, true, 0x00, 0xD0, 0x00,
+    }
+    CRC_8_SMBUS("CRC-8/SMBUS", "CRC-8") = {
+        8, 0x07, 0x00, false, false, 0x00, 0xF4, 0x00,
+    }
+    CRC_8_WCDMA("CRC-8/WCDMA") = {
+        8, 0x9B, 0x00, true, true, 0x00, 0x25, 0x00,
+    }
+    CRC_16_ARC("CRC-16/ARC", "ARC", "CRC-16", "CRC-16/LHA", "CRC-IBM") = {
+        16, 0x8005, 0x0000, true, true, 0x0000, 0xBB3D, 0x0000,
+    }
+    CRC_16_DNP("CRC-16/DNP") = {
+        16, 0x3D65, 0x0000, true, true, 0xFFFF, 0xEA82, 0x66C5,
+    }
+    CRC_16_GENIBUS("CRC-16/GENIBUS", "CRC-16/DARC", "CRC-16/EPC", "CRC-16/I-CODE") = {
+        16, 0x1021, 0xFFFF, false, false, 0xFFFF, 0xD64E, 0x1D0F,
+    }
+    CRC_16_IBM_3740("CRC-16/IBM-3740", "CRC-16/AUTOSAR", "CRC-16/CCITT-FALSE") = {
+        16, 0x1021, 0xFFFF, false, false, 0x0000, 0x29B1, 0x0000,
+    }
+    CRC_16_IBM_SDLC(
+        "CRC-16/IBM-SDLC", "CRC-16/ISO-HDLC", "CRC-16/ISO-IEC-14443-3-B", "CRC-16/X-25", "CRC-B", "X-25"
+    ) = {
+        16, 0x1021, 0xFFFF, true, true, 0xFFFF, 0x906E, 0xF0B8,
+    }
+    CRC_16_KERMIT(
+        "CRC-16/KERMIT", "CRC-16/BLUETOOTH", "CRC-16/CCITT", "CRC-16/CCITT-TRUE", "CRC-16/V-41-LSB",
+        "CRC-CCITT", "KERMIT"
+    ) = {
+        16, 0x1021, 0x0000, true, true, 0x0000, 0x2189, 0x0000,
+    }
+    CRC_16_MAXIM_DOW("CRC-16/MAXIM-DOW", "CRC-16/MAXIM") = {
+        16, 0x8005, 0x0000, true, true, 0xFFFF, 0x44C2, 0xB001,
+    }
+    CRC_16_MODBUS("CRC-16/MODBUS", "MODBUS") = {
+        16, 0x8005, 0xFFFF, true, true, 0x0000, 0x4B37, 0x0000,
+    }
+    CRC_16_T10_DIF("CRC-16/T10-DIF") = {
+        16, 0x8BB7, 0x0000, false, false, 0x0000, 0xD0DB, 0x0000,
+    }
+    CRC_16_UMTS("CRC-16/UMTS", "CRC-16/BUYPASS", "CRC-16/VERIFONE") = {
+        16, 0x8005, 0x0000, false, false, 0x0000, 0xFEE8, 0x0000,
+    }
+    CRC_16_USB("CRC-16/USB") = {
+        16, 0x8005, 0xFFFF, true, true, 0xFFFF, 0xB4C8, 0xB001,
+    }
+    CRC_16_XMODEM(
+        "CRC-16/XMODEM", "CRC-16/ACORN", "CRC-16/LTE", "CRC-16/V-41-MSB", "XMODEM", "ZMODEM"
+    ) = {
+        16, 0x1021, 0x0000, false, false, 0x0000, 0x31C3, 0x0000,
+    }
+    CRC_32_AIXM("CRC-32/AIXM", "CRC-32Q") = {
+        32, 0x814141AB, 0x00000000, false, false, 0x00000000, 0x3010BF7F, 0x00000000,
+    }
+    CRC_32_AUTOSAR("CRC-32/AUTOSAR") = {
+        32, 0xF4ACFB13, 0xFFFFFFFF, true, true, 0xFFFFFFFF, 0x1697D06A, 0x904CDDBF,
+    }
+    CRC_32_BASE91_D("CRC-32/BASE91-D", "CRC-32D") = {
+        32, 0xA833982B, 0xFFFFFFFF, true, true, 0xFFFFFFFF, 0x87315576, 0x45270551,
+    }
+    CRC_32_BZIP2("CRC-32/BZIP2", "CRC-32/AAL5", "CRC-32/DECT-B", "B-CRC-32") = {
+        32, 0x04C11DB7, 0xFFFFFFFF, false, false, 0xFFFFFFFF, 0xFC891918, 0xC704DD7B,
+    }
+    CRC_32_CD_ROM_EDC("CRC-32/CD-ROM-EDC") = {
+        32, 0x8001801B, 0x00000000, true, true, 0x00000000, 0x6EC2EDC4, 0x00000000,
+    }
+    CRC_32_CKSUM("CRC-32/CKSUM", "CKSUM", "CRC-32/POSIX") = {
+        32, 0x04C11DB7, 0x00000000, false, false, 0xFFFFFFFF, 0x765E7680, 0xC704DD7B,
+    }
+    CRC_32_ISCSI(
+        "CRC-32/ISCSI", "CRC-32/BASE91-C", "CRC-32/CASTAGNOLI", "CRC-32/INTERLAKEN", "CRC-32C"
+    ) = {
+        32, 0x1EDC6F41, 0xFFFFFFFF, true, true, 0xFFFFFFFF, 0xE3069283, 0xB798B438,
+    }
+    CRC_32_ISO_HDLC(
+        "CRC-32/ISO-HDLC", "CRC-32", "CRC-32/ADCCP", "CRC-32/V-42", "CRC-32/XZ", "PKZIP"
+    ) = {
+        32, 0x04C11DB7, 0xFFFFFFFF, true, true, 0xFFFFFFFF, 0xCBF43926, 0xDEBB20E3,
+    }
+    CRC_32_JAMCRC("CRC-32/JAMCRC", "JAMCRC") = {
+        32, 0x04C11DB7, 0xFFFFFFFF, true, true, 0x00000000, 0x340BC6D9, 0x00000000,
+    }
+    CRC_32_MPEG_2("CRC-32/MPEG-2") = {
+        32, 0x04C11DB7, 0xFFFFFFFF, false, false, 0x00000000, 0x0376E6E7, 0x00000000,
+    }
+    CRC_32_XFER("CRC-32/XFER", "XFER") = {
+        32, 0x000000AF, 0x00000000, false, false, 0x00000000, 0xBD0BE338, 0x00000000,
+    }
+    CRC_64_ECMA_182("CRC-64/ECMA-182", "CRC-64") = {
+        64, 0x42F0E1EBA9EA3693, 0x0000000000000000, false, false, 0x0000000000000000,
+        0x6C40DF5F0B497347, 0x0000000000000000,
+    }
+    CRC_64_GO_ISO("CRC-64/GO-ISO") = {
+        64, 0x000000000000001B, 0xFFFFFFFFFFFFFFFF, true, true, 0xFFFFFFFFFFFFFFFF,
+        0xB90956C775A41001, 0x5300000000000000,
+    }
+    CRC_64_MS("CRC-64/MS") = {
+        64, 0x259C84CBA6426349, 0xFFFFFFFFFFFFFFFF, true, true, 0x0000000000000000,
+        0x75D4B74F024ECEEA, 0x0000000000000000,
+    }
+    CRC_64_NVME("CRC-64/NVME") = {
+        64, 0xAD93D23594C93659, 0xFFFFFFFFFFFFFFFF, true, true, 0xFFFFFFFFFFFFFFFF,
+        0xAE8B14860A799888, 0xF310303B2B6F6E42,
+    }
+    CRC_64_WE("CRC-64/WE") = {
+        64, 0x42F0E1EBA9EA3693, 0xFFFFFFFFFFFFFFFF, false, false, 0xFFFFFFFFFFFFFFFF,
+        0x62EC59E3F1A4F00A, 0xFCACBEBD5931A992,
+    }
+    CRC_64_XZ("CRC-64/XZ", "CRC-64/GO-ECMA") = {
+        64, 0x42F0E1EBA9EA3693, 0xFFFFFFFFFFFFFFFF, true, true, 0xFFFFFFFFFFFFFFFF,
+        0x995DC9BBDF1939FA, 0x49958C9ABD7D353F,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::{Crc, CrcDigest, CrcWidth};
+
+    /// Checks the check value on every backend, and that the crc of a codeword without `xorout`
+    /// is the residue
+    fn test_algorithm<W: CrcWidth>(params: CrcParams) {
+        let name = find_by_params(params);
+        for backend in Crc::<W>::supported_backends(params) {
+            let mut crc = Crc::<W>::new_with_backend(params, backend).unwrap();
+            crc.update(b"123456789");
+            assert_eq!(crc.digest().into(), params.check, "{} {:?}", name, backend);
+        }
+
+        // the codeword is the message followed by the crc, least significant byte first
+        // when refout, as the receiver processes it in the same order as the message
+        let bytes = (params.width / 8) as usize;
+        let mut codeword = b"123456789".to_vec();
+        if params.refout {
+            codeword.extend_from_slice(&params.check.to_le_bytes()[..bytes]);
+        } else {
+            codeword.extend_from_slice(&params.check.to_be_bytes()[8 - bytes..]);
+        }
+        let mut crc = Crc::<W>::new_naive_with_params(params);
+        crc.update(&codeword);
+        let residue: u64 = crc.digest().into() ^ params.xorout;
+        assert_eq!(residue, params.residue, "{}", name);
+    }
+
+    fn find_by_params(params: CrcParams) -> &'static str {
+        ALGORITHMS
+            .iter()
+            .find(|algorithm| algorithm.params == params)
+            .unwrap()
+            .name
+    }
+
+    #[test]
+    fn test_catalogue() {
+        for algorithm in ALGORITHMS {
+            let params = algorithm.params;
+            let check = match params.width {
+                8 => {
+                    test_algorithm::<u8>(params);
+                    Crc::<u8>::const_checksum(params, b"123456789") as u64
+                }
+                16 => {
+                    test_algorithm::<u16>(params);
+                    Crc::<u16>::const_checksum(params, b"123456789") as u64
+                }
+                32 => {
+                    test_algorithm::<u32>(params);
+                    Crc::<u32>::const_checksum(params, b"123456789") as u64
+                }
+                64 => {
+                    test_algorithm::<u64>(params);
+                    Crc::<u64>::const_checksum(params, b"123456789")
+                }
+                width => panic!("unsupported width {}", width),
+            };
+            assert_eq!(check, params.check, "{}", algorithm.name);
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup("CRC-32/ISCSI"), Some(CRC_32_ISCSI));
+        assert_eq!(lookup("crc-32c"), Some(CRC_32_ISCSI));
+        assert_eq!(lookup("CRC-16/CCITT-FALSE"), Some(CRC_16_IBM_3740));
+        assert_eq!(lookup("CRC-64/GO-ECMA"), Some(CRC_64_XZ));
+        assert_eq!(
+            find("x-25").map(|algorithm| algorithm.name),
+            Some("CRC-16/IBM-SDLC")
+        );
+        assert_eq!(lookup("CRC-32/UNKNOWN"), None);
+
+        for (i, algorithm) in ALGORITHMS.iter().enumerate() {
+            assert_eq!(find(algorithm.name), Some(algorithm));
+            for alias in algorithm.aliases {
+                assert_eq!(find(alias), Some(algorithm));
+            }
+            // names and aliases must be unique for the lookup to be unambiguous
+            for other in &ALGORITHMS[i + 1..] {
+                assert!(!other.is_named(algorithm.name));
+                assert!(algorithm.aliases.iter().all(|alias| !other.is_named(alias)));
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::catalogue::{
+    CRC_16_IBM_3740, CRC_16_IBM_SDLC, CRC_16_KERMIT, CRC_16_MODBUS, CRC_16_XMODEM,
+};
+use crate::crc::fixed_crc;
+
+fixed_crc! {
+    /// CRC-16/MODBUS, used by the modbus RTU protocol
+    Crc16Modbus(u16, CRC_16_MODBUS)
+}
+
+fixed_crc! {
+    /// CRC-16/CCITT-FALSE, also known as CRC-16/IBM-3740 and CRC-16/AUTOSAR
+    Crc16CcittFalse(u16, CRC_16_IBM_3740)
+}
+
+fixed_crc! {
+    /// CRC-16/XMODEM, also known as CRC-16/ACORN and CRC-16/V-41-MSB
+    Crc16Xmodem(u16, CRC_16_XMODEM)
+}
+
+fixed_crc! {
+    /// CRC-16/KERMIT, also known as CRC-16/CCITT and CRC-16/V-41-LSB
+    Crc16Kermit(u16, CRC_16_KERMIT)
+}
+
+fixed_crc! {
+    /// CRC-16/IBM-SDLC, also known as CRC-16/X-25 and CRC-16/ISO-HDLC
+    Crc16X25(u16, CRC_16_IBM_SDLC)
+}
+
+/// CRC-16/IBM-SDLC, an alias of [Crc16X25]
+pub type Crc16IbmSdlc = Crc16X25;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check16::CustomCrc16;
+    use crate::test_util::test_fixed_crc;
+
+    test_fixed_crc!(test_crc16_modbus, Crc16Modbus, CustomCrc16, CRC_16_MODBUS);
+    test_fixed_crc!(
+        test_crc16_ccitt_false,
+        Crc16CcittFalse,
+        CustomCrc16,
+        CRC_16_IBM_3740
+    );
+    test_fixed_crc!(test_crc16_xmodem, Crc16Xmodem, CustomCrc16, CRC_16_XMODEM);
+    test_fixed_crc!(test_crc16_kermit, Crc16Kermit, CustomCrc16, CRC_16_KERMIT);
+    test_fixed_crc!(test_crc16_x25, Crc16IbmSdlc, CustomCrc16, CRC_16_IBM_SDLC);
+
+    #[test]
+    fn test_crc16_const_checksum() {
+        const CHECK: u16 = Crc16Modbus::const_checksum(b"123456789");
+        assert_eq!(CHECK, 0x4B37);
+        const CUSTOM_CHECK: u16 = CustomCrc16::const_checksum(CRC_16_IBM_SDLC, b"123456789");
+        assert_eq!(CUSTOM_CHECK, 0x906E);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_crc16_parallel() {
+        use crate::test_util::{checksum, pseudo_random_data};
+
+        let data = pseudo_random_data(5000);
+        let expected = checksum(Crc16X25::new_lookup(), &data);
+        for threads in [1, 2, 3, 8] {
+            assert_eq!(Crc16X25::checksum_parallel(&data, threads), expected);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+mod crc16;
+mod custom_crc16;
+
+use crate::crc::CrcCombiner;
+
+/// Combines crc16s, see [CrcCombiner]
+pub type Crc16Combiner = CrcCombiner<u16>;
+
+pub use crc16::{Crc16CcittFalse, Crc16IbmSdlc, Crc16Kermit, Crc16Modbus, Crc16X25, Crc16Xmodem};
+pub use custom_crc16::CustomCrc16;
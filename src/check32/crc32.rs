@@ -1,28 +1,46 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::backend::{Backend, UnsupportedBackend};
+use crate::catalogue::CRC_32_ISO_HDLC;
 use crate::check32::custom_crc32::CustomCrc32;
-use crate::check32::Crc32Digest;
+use crate::check32::Crc32Combiner;
+use crate::crc::{self, CrcDigest};
+use crate::params::CrcParams;
+
+#[cfg(feature = "parallel")]
+use crate::parallel;
 
 use super::UpdateFn;
 
+#[cfg(all(feature = "hardware", any(target_arch = "x86", target_arch = "x86_64")))]
+use crate::check32::platform::x86::compute_crc;
+#[cfg(all(feature = "hardware", target_arch = "x86_64"))]
+use crate::check32::platform::x86::{compute_crc_avx2, compute_crc_avx512};
+
+#[cfg(all(feature = "hardware", target_arch = "aarch64"))]
+use crate::check32::platform::arm::compute_crc;
 #[cfg(all(feature = "hardware", feature = "nightly", target_arch = "aarch64"))]
 use crate::check32::platform::arm::compute_crc32_hardware_aarch64;
-#[cfg(all(feature = "hardware", feature = "nightly", target_arch = "aarch64"))]
-use std::arch::is_aarch64_feature_detected;
 
-const CRC32_POLYNOMIAL: u32 = 0x04C11DB7;
+const CRC32_PARAMS: CrcParams = CRC_32_ISO_HDLC;
+const CRC32_POLYNOMIAL: u32 = CRC32_PARAMS.poly as u32;
 const CRC32_LOOKUP_TABLE: [[u32; 256]; 16] =
     CustomCrc32::generate_lookup_table_16(CRC32_POLYNOMIAL);
 
-#[cfg(feature = "hardware")]
-const CRC32_POLYNOMIAL_64: u64 = 0x104C11DB7u64;
 #[cfg(feature = "hardware")]
 const REVERSE_CRC32_POLYNOMIAL_64: u64 = 0x1DB710641u64;
 #[cfg(feature = "hardware")]
 const CRC32_SIMD_CONSTANTS: [u64; 7] =
-    CustomCrc32::generate_simd_reflected_constants(CRC32_POLYNOMIAL_64);
+    CustomCrc32::generate_simd_reflected_constants(CRC32_POLYNOMIAL);
+#[cfg(feature = "hardware")]
+const CRC32_SIMD_WIDE_CONSTANTS: [u64; 6] =
+    CustomCrc32::generate_simd_wide_reflected_constants(CRC32_POLYNOMIAL);
 
+#[derive(Clone)]
 pub struct Crc32 {
     state: u32,
     compute: UpdateFn,
+    backend: Backend,
 }
 
 impl Crc32 {
@@ -31,6 +49,7 @@ impl Crc32 {
         Self {
             state: 0,
             compute: Self::compute_naive,
+            backend: Backend::Naive,
         }
     }
 
@@ -39,6 +58,7 @@ impl Crc32 {
         Self {
             state: 0,
             compute: Self::compute_lookup,
+            backend: Backend::Lookup,
         }
     }
 
@@ -47,58 +67,234 @@ impl Crc32 {
     /// - For aarch64 platform it would use core::arch::aarch64::__crc32* intrinsics like <core::arch::aarch64::__crc32d>
     /// - Otherwise defaults to table lookup approach
     pub fn new_hardware() -> Self {
-        Self {
-            state: 0,
-            compute: Self::compute_hardware,
-        }
+        Self::new_with_backend(Backend::Hardware).unwrap_or_else(|_| Self::new_lookup())
     }
 
     #[cfg(feature = "hardware")]
     /// Creates a new `Crc32` using simd intrinsics based on
     /// [intel's paper](https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/fast-crc-computation-generic-polynomials-pclmulqdq-paper.pdf)
     /// - x86 and x86_64 requires the cpu features sse4.2 and pclmulqdq
+    /// - x86_64 additionally folds 512 or 256 bits per register when vpclmulqdq with avx512f or avx2 is available
     /// - aarch64 requires the cpu features neon and aes
     /// - Otherwise defaults to using hardware crc intrinsics
     pub fn new_simd() -> Self {
-        Self {
+        Backend::SIMD
+            .into_iter()
+            .chain([Backend::Hardware])
+            .find_map(|backend| Self::new_with_backend(backend).ok())
+            .unwrap_or_else(Self::new_lookup)
+    }
+
+    /// Creates a new `Crc32` using the given backend. Unlike the other constructors this does
+    /// not fall back to a slower backend, and fails if the backend is not supported.
+    pub fn new_with_backend(backend: Backend) -> Result<Self, UnsupportedBackend> {
+        let compute = Self::select(backend).ok_or(UnsupportedBackend(backend))?;
+        Ok(Self {
             state: 0,
-            compute: Self::compute_simd,
+            compute,
+            backend,
+        })
+    }
+
+    /// Returns the backends `Crc32` supports on this cpu with the enabled crate features
+    pub fn supported_backends() -> impl Iterator<Item = Backend> {
+        Backend::ALL
+            .into_iter()
+            .filter(|&backend| Self::select(backend).is_some())
+    }
+
+    /// Returns the backend this instance computes crcs with, after falling back from
+    /// unsupported cpu features
+    pub fn selected_backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Resets the crc to its initial state, keeping the selected backend
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Updates digest with data split across up to `threads` threads, each using the approach
+    /// this instance was created with. The partial crcs are merged using [combine](Self::combine).
+    pub fn update_parallel(&mut self, data: &[u8], threads: usize) {
+        self.state =
+            parallel::update_parallel(self.state, data, threads, 0, self.compute, Self::combine);
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Computes the `Crc32` of data using up to `threads` threads, see [update_parallel](Self::update_parallel).
+    /// - Uses the simd approach when the `hardware` feature is enabled
+    /// - Otherwise uses table lookup approach
+    pub fn checksum_parallel(data: &[u8], threads: usize) -> u32 {
+        #[cfg(feature = "hardware")]
+        let mut crc = Self::new_simd();
+        #[cfg(not(feature = "hardware"))]
+        let mut crc = Self::new_lookup();
+        crc.update_parallel(data, threads);
+        crc.digest()
+    }
+
+    /// Computes the crc of `A || B` given `crc_a`, `crc_b` and the length of `B` in bytes
+    pub fn combine(crc_a: u32, crc_b: u32, len_b: u64) -> u32 {
+        Self::combiner(len_b).combine(crc_a, crc_b)
+    }
+
+    /// Creates a [Crc32Combiner] for combining crcs where the second chunk is `len_b` bytes long
+    pub fn combiner(len_b: u64) -> Crc32Combiner {
+        Crc32Combiner::new(CRC32_PARAMS, len_b)
+    }
+
+    /// Computes the crc of data after replacing `old` at `offset` with `new`, given `old_crc`, the
+    /// crc of the original `total_len` bytes, see [Crc::patch](crate::crc::Crc::patch).
+    pub fn patch(&self, old_crc: u32, total_len: u64, offset: u64, old: &[u8], new: &[u8]) -> u32 {
+        crc::patch(
+            CRC32_PARAMS,
+            self.compute,
+            old_crc,
+            total_len,
+            offset,
+            old,
+            new,
+        )
+    }
+
+    /// Returns the 4 bytes which, written over `data[offset..offset + 4]`, make the crc of
+    /// `data` equal to `target`, e.g. to keep the expected checksum of a patched firmware image.
+    ///
+    /// # Panics
+    /// Panics if `offset + 4` exceeds the length of `data`.
+    pub fn forge(&self, data: &[u8], offset: usize, target: u32) -> [u8; 4] {
+        let bytes = crc::forge(CRC32_PARAMS, self.compute, 0, data, offset, target)
+            .expect("the polynomial has the x^0 term");
+        (bytes as u32).to_le_bytes()
+    }
+
+    /// Computes the crc of `data` using the table lookup approach, usable in const contexts
+    pub const fn const_checksum(data: &[u8]) -> u32 {
+        CustomCrc32::const_lookup(0, &CRC32_LOOKUP_TABLE[0], data)
+    }
+
+    fn select(backend: Backend) -> Option<UpdateFn> {
+        match backend {
+            Backend::Naive => Some(Self::compute_naive),
+            Backend::Lookup => Some(Self::compute_lookup),
+            #[cfg(feature = "hardware")]
+            Backend::Hardware => Self::select_hardware(),
+            #[cfg(feature = "hardware")]
+            _ => Self::select_simd(backend),
+            #[cfg(not(feature = "hardware"))]
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "hardware")]
+    fn select_hardware() -> Option<UpdateFn> {
+        #[cfg(all(target_arch = "aarch64", feature = "nightly"))]
+        if aarch64_feature_detected!("crc") {
+            return Some(Self::compute_hardware);
+        }
+        None
+    }
+
+    #[cfg(feature = "hardware")]
+    fn select_simd(backend: Backend) -> Option<UpdateFn> {
+        if !CustomCrc32::is_simd_supported(backend) {
+            return None;
+        }
+        match backend {
+            #[cfg(target_arch = "x86_64")]
+            Backend::SimdAvx512 => Some(Self::compute_simd_avx512),
+            #[cfg(target_arch = "x86_64")]
+            Backend::SimdAvx2 => Some(Self::compute_simd_avx2),
+            #[cfg(all(target_arch = "aarch64", feature = "nightly"))]
+            _ if aarch64_feature_detected!("crc") => Some(Self::compute_simd_hardware),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+            _ => Some(Self::compute_simd),
+            #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+            _ => None,
         }
     }
 
     fn compute_lookup(prev_crc: u32, data: &[u8]) -> u32 {
-        CustomCrc32::crc32_lookup(prev_crc, &CRC32_LOOKUP_TABLE, data)
+        CustomCrc32::lookup(prev_crc, &CRC32_LOOKUP_TABLE, data)
     }
 
     fn compute_naive(prev_crc: u32, data: &[u8]) -> u32 {
-        CustomCrc32::crc32_naive(prev_crc, CRC32_POLYNOMIAL, data)
+        CustomCrc32::naive(prev_crc, CRC32_POLYNOMIAL, data)
     }
 
-    #[cfg(feature = "hardware")]
+    #[cfg(all(feature = "hardware", feature = "nightly", target_arch = "aarch64"))]
     fn compute_hardware(prev_crc: u32, data: &[u8]) -> u32 {
-        unsafe {
-            #[cfg(all(target_arch = "aarch64", feature = "nightly"))]
-            if is_aarch64_feature_detected!("crc") {
-                return compute_crc32_hardware_aarch64(prev_crc, data);
-            }
-        }
-        Self::compute_lookup(prev_crc, data)
+        // safety: only selected when the crc cpu feature is detected
+        unsafe { compute_crc32_hardware_aarch64(prev_crc, data) }
     }
 
-    #[cfg(feature = "hardware")]
+    #[cfg(all(
+        feature = "hardware",
+        any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+    ))]
     fn compute_simd(mut prev_crc: u32, mut data: &[u8]) -> u32 {
-        (prev_crc, data) = CustomCrc32::crc32_simd(
-            prev_crc,
-            CRC32_SIMD_CONSTANTS,
-            REVERSE_CRC32_POLYNOMIAL_64,
-            data,
-        );
-        //prev_crc
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe {
+            compute_crc(
+                prev_crc,
+                CRC32_SIMD_CONSTANTS,
+                REVERSE_CRC32_POLYNOMIAL_64,
+                data,
+            )
+        };
+        Self::compute_lookup(prev_crc, data)
+    }
+
+    #[cfg(all(feature = "hardware", feature = "nightly", target_arch = "aarch64"))]
+    fn compute_simd_hardware(mut prev_crc: u32, mut data: &[u8]) -> u32 {
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe {
+            compute_crc(
+                prev_crc,
+                CRC32_SIMD_CONSTANTS,
+                REVERSE_CRC32_POLYNOMIAL_64,
+                data,
+            )
+        };
         Self::compute_hardware(prev_crc, data)
     }
+
+    #[cfg(all(feature = "hardware", target_arch = "x86_64"))]
+    fn compute_simd_avx2(mut prev_crc: u32, mut data: &[u8]) -> u32 {
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe {
+            compute_crc_avx2(
+                prev_crc,
+                CRC32_SIMD_CONSTANTS,
+                CRC32_SIMD_WIDE_CONSTANTS,
+                REVERSE_CRC32_POLYNOMIAL_64,
+                data,
+            )
+        };
+        Self::compute_lookup(prev_crc, data)
+    }
+
+    #[cfg(all(feature = "hardware", target_arch = "x86_64"))]
+    fn compute_simd_avx512(mut prev_crc: u32, mut data: &[u8]) -> u32 {
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe {
+            compute_crc_avx512(
+                prev_crc,
+                CRC32_SIMD_CONSTANTS,
+                CRC32_SIMD_WIDE_CONSTANTS,
+                REVERSE_CRC32_POLYNOMIAL_64,
+                data,
+            )
+        };
+        Self::compute_lookup(prev_crc, data)
+    }
 }
 
-impl Crc32Digest for Crc32 {
+impl CrcDigest for Crc32 {
+    type Output = u32;
+
     fn update(&mut self, data: &[u8]) {
         self.state = (self.compute)(self.state, data);
     }
@@ -106,11 +302,69 @@ impl Crc32Digest for Crc32 {
     fn digest(&self) -> u32 {
         self.state
     }
+
+    fn update_zeros(&mut self, len: u64) {
+        self.state = Self::combiner(len).append_zeros(self.state);
+    }
+}
+
+impl Hasher for Crc32 {
+    /// Returns the crc spread over all 64 bits, as hash maps use the top bits of the hash
+    fn finish(&self) -> u64 {
+        super::spread_hash(self.digest())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+}
+
+impl Default for Crc32 {
+    /// Creates a new `Crc32` using the simd approach when the `hardware` feature is enabled and the
+    /// table lookup approach otherwise
+    fn default() -> Self {
+        #[cfg(feature = "hardware")]
+        return Self::new_simd();
+        #[cfg(not(feature = "hardware"))]
+        return Self::new_lookup();
+    }
+}
+
+/// Builds [Crc32] hashers for use in hash maps. The backend is selected once when the builder is
+/// created, using the simd approach when the `hardware` feature is enabled and the table lookup
+/// approach otherwise.
+#[derive(Clone)]
+pub struct Crc32BuildHasher {
+    hasher: Crc32,
+}
+
+impl Crc32BuildHasher {
+    /// Creates a new `Crc32BuildHasher`
+    pub fn new() -> Self {
+        Self {
+            hasher: Crc32::default(),
+        }
+    }
+}
+
+impl Default for Crc32BuildHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for Crc32BuildHasher {
+    type Hasher = Crc32;
+
+    fn build_hasher(&self) -> Crc32 {
+        self.hasher.clone()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{assert_forge, assert_patch, assert_update_zeros};
 
     static EMPTY_DATA: &[u8; 0] = b"";
     static SMALL_DATA_1: &[u8; 9] = b"123456789";
@@ -154,6 +408,7 @@ mod tests {
         test_lookup(LARGE_DATA_2, LARGE_DATA_2_CRC32);
     }
 
+    #[cfg(feature = "hardware")]
     fn test_simd(data: &[u8], expected_crc: u32) {
         let mut crc = Crc32::new_simd();
         crc.update(data);
@@ -169,4 +424,70 @@ mod tests {
         test_simd(LARGE_DATA_1, LARGE_DATA_1_CRC32);
         test_simd(LARGE_DATA_2, LARGE_DATA_2_CRC32);
     }
+
+    #[test]
+    fn test_crc32_combine() {
+        for split in [0, 1, 9, 100, LARGE_DATA_2.len()] {
+            let (a, b) = LARGE_DATA_2.split_at(split);
+            let mut crc_a = Crc32::new_lookup();
+            crc_a.update(a);
+            let mut crc_b = Crc32::new_lookup();
+            crc_b.update(b);
+            let len_b = b.len() as u64;
+            assert_eq!(
+                Crc32::combine(crc_a.digest(), crc_b.digest(), len_b),
+                LARGE_DATA_2_CRC32
+            );
+            assert_eq!(
+                Crc32::combiner(len_b).combine(crc_a.digest(), crc_b.digest()),
+                LARGE_DATA_2_CRC32
+            );
+        }
+    }
+
+    #[test]
+    fn test_crc32_hasher() {
+        let mut hasher = Crc32BuildHasher::new().build_hasher();
+        hasher.write(SMALL_DATA_1);
+        assert_eq!(
+            hasher.finish(),
+            crate::check32::spread_hash(SMALL_DATA_1_CRC32)
+        );
+        assert_ne!(hasher.finish() >> 32, 0);
+    }
+
+    #[test]
+    fn test_crc32_const_checksum() {
+        const SMALL_DATA_1_CONST_CRC32: u32 = Crc32::const_checksum(SMALL_DATA_1);
+        assert_eq!(SMALL_DATA_1_CONST_CRC32, SMALL_DATA_1_CRC32);
+        assert_eq!(Crc32::const_checksum(EMPTY_DATA), EMPTY_DATA_CRC32);
+        assert_eq!(Crc32::const_checksum(SMALL_DATA_2), SMALL_DATA_2_CRC32);
+        assert_eq!(Crc32::const_checksum(LARGE_DATA_1), LARGE_DATA_1_CRC32);
+        assert_eq!(Crc32::const_checksum(LARGE_DATA_2), LARGE_DATA_2_CRC32);
+    }
+
+    #[test]
+    fn test_crc32_update_zeros() {
+        for backend in Crc32::supported_backends() {
+            assert_update_zeros(Crc32::new_with_backend(backend).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_crc32_patch() {
+        for backend in Crc32::supported_backends() {
+            let crc = Crc32::new_with_backend(backend).unwrap();
+            assert_patch(&crc, |old_crc, total_len, offset, old, new| {
+                crc.patch(old_crc, total_len, offset, old, new)
+            });
+        }
+    }
+
+    #[test]
+    fn test_crc32_forge() {
+        for backend in Crc32::supported_backends() {
+            let crc = Crc32::new_with_backend(backend).unwrap();
+            assert_forge(&crc, |data, offset, target| crc.forge(data, offset, target));
+        }
+    }
 }
@@ -1,32 +1,65 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::backend::{Backend, UnsupportedBackend};
+use crate::catalogue::CRC_32_ISCSI;
 use crate::check32::custom_crc32::CustomCrc32;
-use crate::check32::{Crc32Digest, UpdateFn};
+use crate::check32::{Crc32Combiner, UpdateFn};
+use crate::crc::{self, CrcDigest};
+use crate::params::CrcParams;
+
+#[cfg(feature = "parallel")]
+use crate::parallel;
 
+#[cfg(all(feature = "hardware", any(target_arch = "x86", target_arch = "x86_64")))]
+use crate::check32::platform::x86::compute_crc;
 #[cfg(all(feature = "hardware", target_arch = "x86_64"))]
-use crate::check32::platform::x86::compute_crc32c_hardware_x86_64;
+use crate::check32::platform::x86::{
+    compute_crc32c_hardware_interleaved_x86_64, compute_crc32c_hardware_x86_64, compute_crc_avx2,
+    compute_crc_avx512,
+};
 
 #[cfg(all(feature = "hardware", target_arch = "x86"))]
 use crate::check32::platform::x86::compute_crc32c_hardware_x86;
 
+#[cfg(all(feature = "hardware", target_arch = "aarch64"))]
+use crate::check32::platform::arm::compute_crc;
 #[cfg(all(feature = "hardware", feature = "nightly", target_arch = "aarch64"))]
-use crate::check32::platform::arm::compute_crc32c_hardware_aarch64;
-#[cfg(all(feature = "hardware", feature = "nightly", target_arch = "aarch64"))]
-use std::arch::is_aarch64_feature_detected;
+use crate::check32::platform::arm::{
+    compute_crc32c_hardware_aarch64, compute_crc32c_hardware_interleaved_aarch64,
+};
 
-const CRC32C_POLYNOMIAL: u32 = 0x1EDC6F41;
+const CRC32C_PARAMS: CrcParams = CRC_32_ISCSI;
+const CRC32C_POLYNOMIAL: u32 = CRC32C_PARAMS.poly as u32;
 const CRC32C_LOOKUP_TABLE: [[u32; 256]; 16] =
     CustomCrc32::generate_lookup_table_16(CRC32C_POLYNOMIAL);
 
-#[cfg(feature = "hardware")]
-const CRC32C_POLYNOMIAL_64: u64 = 0x11EDC6F41u64;
 #[cfg(feature = "hardware")]
 const REVERSE_CRC32C_POLYNOMIAL_64: u64 = 0x105ec76f1u64;
 #[cfg(feature = "hardware")]
 const CRC32C_SIMD_CONSTANTS: [u64; 7] =
-    CustomCrc32::generate_simd_reflected_constants(CRC32C_POLYNOMIAL_64);
+    CustomCrc32::generate_simd_reflected_constants(CRC32C_POLYNOMIAL);
+#[cfg(feature = "hardware")]
+const CRC32C_SIMD_WIDE_CONSTANTS: [u64; 6] =
+    CustomCrc32::generate_simd_wide_reflected_constants(CRC32C_POLYNOMIAL);
+#[cfg(all(
+    feature = "hardware",
+    any(
+        target_arch = "x86_64",
+        all(feature = "nightly", target_arch = "aarch64")
+    )
+))]
+const CRC32C_INTERLEAVE_CONSTANTS: [u64; 4] = {
+    use crate::check32::platform::{LONG_STRIPE, SHORT_STRIPE};
+    let long = CustomCrc32::generate_interleave_constants(CRC32C_POLYNOMIAL, LONG_STRIPE);
+    let short = CustomCrc32::generate_interleave_constants(CRC32C_POLYNOMIAL, SHORT_STRIPE);
+    [long[0], long[1], short[0], short[1]]
+};
 
+#[derive(Clone)]
 pub struct Crc32C {
     state: u32,
     compute: UpdateFn,
+    backend: Backend,
 }
 
 impl Crc32C {
@@ -35,6 +68,7 @@ impl Crc32C {
         Self {
             state: 0,
             compute: Self::compute_naive,
+            backend: Backend::Naive,
         }
     }
 
@@ -43,6 +77,7 @@ impl Crc32C {
         Self {
             state: 0,
             compute: Self::compute_lookup,
+            backend: Backend::Lookup,
         }
     }
 
@@ -50,69 +85,285 @@ impl Crc32C {
     /// Creates a new `Crc32C` using hardware crc intrinsics
     /// - For x86 and x86_64 platform it would use core::arch::x86_64::_mm_crc32_u* intrinsics like <core::arch::x86_64::_mm_crc32_u64>
     /// - For aarch64 platform it would use core::arch::aarch64::__crc32c* intrinsics like <core::arch::aarch64::__crc32cd>
+    /// - On x86_64 with pclmulqdq and aarch64 with aes, large inputs are split into three interleaved
+    ///   streams which are recombined using carry less multiplication
     /// - Otherwise defaults to table lookup approach
     pub fn new_hardware() -> Self {
-        Self {
-            state: 0,
-            compute: Self::compute_hardware,
-        }
+        Self::new_with_backend(Backend::Hardware).unwrap_or_else(|_| Self::new_lookup())
     }
 
     #[cfg(feature = "hardware")]
     /// Creates a new `Crc32C` using simd intrinsics based on
     /// [intel's paper](https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/fast-crc-computation-generic-polynomials-pclmulqdq-paper.pdf)
     /// - x86 and x86_64 requires the cpu features sse4.2, pclmulqdq
+    /// - x86_64 additionally folds 512 or 256 bits per register when vpclmulqdq with avx512f or avx2 is available
     /// - aarch64 requires the cpu features neon, aes
     /// - Otherwise defaults to using hardware crc intrinsics
     pub fn new_simd() -> Self {
-        Self {
+        Backend::SIMD
+            .into_iter()
+            .chain([Backend::Hardware])
+            .find_map(|backend| Self::new_with_backend(backend).ok())
+            .unwrap_or_else(Self::new_lookup)
+    }
+
+    /// Creates a new `Crc32C` using the given backend. Unlike the other constructors this does
+    /// not fall back to a slower backend, and fails if the backend is not supported.
+    pub fn new_with_backend(backend: Backend) -> Result<Self, UnsupportedBackend> {
+        let compute = Self::select(backend).ok_or(UnsupportedBackend(backend))?;
+        Ok(Self {
             state: 0,
-            compute: Self::compute_simd,
+            compute,
+            backend,
+        })
+    }
+
+    /// Returns the backends `Crc32C` supports on this cpu with the enabled crate features
+    pub fn supported_backends() -> impl Iterator<Item = Backend> {
+        Backend::ALL
+            .into_iter()
+            .filter(|&backend| Self::select(backend).is_some())
+    }
+
+    /// Returns the backend this instance computes crcs with, after falling back from
+    /// unsupported cpu features
+    pub fn selected_backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Resets the crc to its initial state, keeping the selected backend
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Updates digest with data split across up to `threads` threads, each using the approach
+    /// this instance was created with. The partial crcs are merged using [combine](Self::combine).
+    pub fn update_parallel(&mut self, data: &[u8], threads: usize) {
+        self.state =
+            parallel::update_parallel(self.state, data, threads, 0, self.compute, Self::combine);
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Computes the `Crc32C` of data using up to `threads` threads, see [update_parallel](Self::update_parallel).
+    /// - Uses the simd approach when the `hardware` feature is enabled
+    /// - Otherwise uses table lookup approach
+    pub fn checksum_parallel(data: &[u8], threads: usize) -> u32 {
+        #[cfg(feature = "hardware")]
+        let mut crc = Self::new_simd();
+        #[cfg(not(feature = "hardware"))]
+        let mut crc = Self::new_lookup();
+        crc.update_parallel(data, threads);
+        crc.digest()
+    }
+
+    /// Computes the crc of `A || B` given `crc_a`, `crc_b` and the length of `B` in bytes
+    pub fn combine(crc_a: u32, crc_b: u32, len_b: u64) -> u32 {
+        Self::combiner(len_b).combine(crc_a, crc_b)
+    }
+
+    /// Creates a [Crc32Combiner] for combining crcs where the second chunk is `len_b` bytes long
+    pub fn combiner(len_b: u64) -> Crc32Combiner {
+        Crc32Combiner::new(CRC32C_PARAMS, len_b)
+    }
+
+    /// Computes the crc of data after replacing `old` at `offset` with `new`, given `old_crc`, the
+    /// crc of the original `total_len` bytes, see [Crc::patch](crate::crc::Crc::patch).
+    pub fn patch(&self, old_crc: u32, total_len: u64, offset: u64, old: &[u8], new: &[u8]) -> u32 {
+        crc::patch(
+            CRC32C_PARAMS,
+            self.compute,
+            old_crc,
+            total_len,
+            offset,
+            old,
+            new,
+        )
+    }
+
+    /// Returns the 4 bytes which, written over `data[offset..offset + 4]`, make the crc of
+    /// `data` equal to `target`, e.g. to keep the expected checksum of a patched firmware image.
+    ///
+    /// # Panics
+    /// Panics if `offset + 4` exceeds the length of `data`.
+    pub fn forge(&self, data: &[u8], offset: usize, target: u32) -> [u8; 4] {
+        let bytes = crc::forge(CRC32C_PARAMS, self.compute, 0, data, offset, target)
+            .expect("the polynomial has the x^0 term");
+        (bytes as u32).to_le_bytes()
+    }
+
+    /// Computes the crc of `data` using the table lookup approach, usable in const contexts
+    pub const fn const_checksum(data: &[u8]) -> u32 {
+        CustomCrc32::const_lookup(0, &CRC32C_LOOKUP_TABLE[0], data)
+    }
+
+    fn select(backend: Backend) -> Option<UpdateFn> {
+        match backend {
+            Backend::Naive => Some(Self::compute_naive),
+            Backend::Lookup => Some(Self::compute_lookup),
+            #[cfg(feature = "hardware")]
+            Backend::Hardware => Self::select_hardware(),
+            #[cfg(feature = "hardware")]
+            _ => Self::select_simd(backend),
+            #[cfg(not(feature = "hardware"))]
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "hardware")]
+    fn select_hardware() -> Option<UpdateFn> {
+        #[cfg(target_arch = "x86_64")]
+        if x86_feature_detected!("sse4.2") {
+            if x86_feature_detected!("pclmulqdq") {
+                return Some(Self::compute_hardware_interleaved);
+            }
+            return Some(Self::compute_hardware);
+        }
+        #[cfg(target_arch = "x86")]
+        if x86_feature_detected!("sse4.2") {
+            return Some(Self::compute_hardware);
+        }
+        #[cfg(all(target_arch = "aarch64", feature = "nightly"))]
+        if aarch64_feature_detected!("crc") {
+            if aarch64_feature_detected!("aes") {
+                return Some(Self::compute_hardware_interleaved);
+            }
+            return Some(Self::compute_hardware);
+        }
+        None
+    }
+
+    #[cfg(feature = "hardware")]
+    fn select_simd(backend: Backend) -> Option<UpdateFn> {
+        if !CustomCrc32::is_simd_supported(backend) {
+            return None;
+        }
+        match backend {
+            #[cfg(target_arch = "x86_64")]
+            Backend::SimdAvx512 => Some(Self::compute_simd_avx512),
+            #[cfg(target_arch = "x86_64")]
+            Backend::SimdAvx2 => Some(Self::compute_simd_avx2),
+            #[cfg(all(target_arch = "aarch64", feature = "nightly"))]
+            _ if aarch64_feature_detected!("crc") => Some(Self::compute_simd_hardware),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+            _ => Some(Self::compute_simd),
+            #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+            _ => None,
         }
     }
 
     fn compute_naive(prev_crc: u32, data: &[u8]) -> u32 {
-        CustomCrc32::crc32_naive(prev_crc, CRC32C_POLYNOMIAL, data)
+        CustomCrc32::naive(prev_crc, CRC32C_POLYNOMIAL, data)
     }
 
     fn compute_lookup(prev_crc: u32, data: &[u8]) -> u32 {
-        CustomCrc32::crc32_lookup(prev_crc, &CRC32C_LOOKUP_TABLE, data)
+        CustomCrc32::lookup(prev_crc, &CRC32C_LOOKUP_TABLE, data)
     }
 
-    #[cfg(feature = "hardware")]
+    #[cfg(all(feature = "hardware", target_arch = "x86_64"))]
+    fn compute_hardware(prev_crc: u32, data: &[u8]) -> u32 {
+        // safety: only selected when the sse4.2 cpu feature is detected
+        unsafe { compute_crc32c_hardware_x86_64(prev_crc, data) }
+    }
+
+    #[cfg(all(feature = "hardware", target_arch = "x86"))]
+    fn compute_hardware(prev_crc: u32, data: &[u8]) -> u32 {
+        // safety: only selected when the sse4.2 cpu feature is detected
+        unsafe { compute_crc32c_hardware_x86(prev_crc, data) }
+    }
+
+    #[cfg(all(feature = "hardware", feature = "nightly", target_arch = "aarch64"))]
     fn compute_hardware(prev_crc: u32, data: &[u8]) -> u32 {
+        // safety: only selected when the crc cpu feature is detected
+        unsafe { compute_crc32c_hardware_aarch64(prev_crc, data) }
+    }
+
+    #[cfg(all(feature = "hardware", target_arch = "x86_64"))]
+    fn compute_hardware_interleaved(prev_crc: u32, data: &[u8]) -> u32 {
+        // safety: only selected when the sse4.2 and pclmulqdq cpu features are detected
         unsafe {
-            #[cfg(target_arch = "x86_64")]
-            if is_x86_feature_detected!("sse4.2") {
-                return compute_crc32c_hardware_x86_64(prev_crc, data);
-            }
-            #[cfg(target_arch = "x86")]
-            if is_x86_feature_detected!("sse4.2") {
-                return compute_crc32c_hardware_x86(prev_crc, data);
-            }
-            #[cfg(all(target_arch = "aarch64", feature = "nightly"))]
-            if is_aarch64_feature_detected!("crc") {
-                return compute_crc32c_hardware_aarch64(prev_crc, data);
-            }
+            compute_crc32c_hardware_interleaved_x86_64(prev_crc, CRC32C_INTERLEAVE_CONSTANTS, data)
         }
+    }
 
-        Self::compute_lookup(prev_crc, data)
+    #[cfg(all(feature = "hardware", feature = "nightly", target_arch = "aarch64"))]
+    fn compute_hardware_interleaved(prev_crc: u32, data: &[u8]) -> u32 {
+        // safety: only selected when the crc and aes cpu features are detected
+        unsafe {
+            compute_crc32c_hardware_interleaved_aarch64(prev_crc, CRC32C_INTERLEAVE_CONSTANTS, data)
+        }
     }
 
-    #[cfg(feature = "hardware")]
+    /// Uses hardware crc intrinsics for the tail on x86 and x86_64 since the simd kernels
+    /// require sse4.2
+    #[cfg(all(
+        feature = "hardware",
+        any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+    ))]
     fn compute_simd(mut prev_crc: u32, mut data: &[u8]) -> u32 {
-        (prev_crc, data) = CustomCrc32::crc32_simd(
-            prev_crc,
-            CRC32C_SIMD_CONSTANTS,
-            REVERSE_CRC32C_POLYNOMIAL_64,
-            data,
-        );
-        //prev_crc
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe {
+            compute_crc(
+                prev_crc,
+                CRC32C_SIMD_CONSTANTS,
+                REVERSE_CRC32C_POLYNOMIAL_64,
+                data,
+            )
+        };
+        #[cfg(target_arch = "aarch64")]
+        return Self::compute_lookup(prev_crc, data);
+        #[cfg(not(target_arch = "aarch64"))]
+        return Self::compute_hardware(prev_crc, data);
+    }
+
+    #[cfg(all(feature = "hardware", feature = "nightly", target_arch = "aarch64"))]
+    fn compute_simd_hardware(mut prev_crc: u32, mut data: &[u8]) -> u32 {
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe {
+            compute_crc(
+                prev_crc,
+                CRC32C_SIMD_CONSTANTS,
+                REVERSE_CRC32C_POLYNOMIAL_64,
+                data,
+            )
+        };
+        Self::compute_hardware(prev_crc, data)
+    }
+
+    #[cfg(all(feature = "hardware", target_arch = "x86_64"))]
+    fn compute_simd_avx2(mut prev_crc: u32, mut data: &[u8]) -> u32 {
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe {
+            compute_crc_avx2(
+                prev_crc,
+                CRC32C_SIMD_CONSTANTS,
+                CRC32C_SIMD_WIDE_CONSTANTS,
+                REVERSE_CRC32C_POLYNOMIAL_64,
+                data,
+            )
+        };
+        Self::compute_hardware(prev_crc, data)
+    }
+
+    #[cfg(all(feature = "hardware", target_arch = "x86_64"))]
+    fn compute_simd_avx512(mut prev_crc: u32, mut data: &[u8]) -> u32 {
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe {
+            compute_crc_avx512(
+                prev_crc,
+                CRC32C_SIMD_CONSTANTS,
+                CRC32C_SIMD_WIDE_CONSTANTS,
+                REVERSE_CRC32C_POLYNOMIAL_64,
+                data,
+            )
+        };
         Self::compute_hardware(prev_crc, data)
     }
 }
 
-impl Crc32Digest for Crc32C {
+impl CrcDigest for Crc32C {
+    type Output = u32;
+
     fn update(&mut self, data: &[u8]) {
         self.state = (self.compute)(self.state, data);
     }
@@ -120,11 +371,69 @@ impl Crc32Digest for Crc32C {
     fn digest(&self) -> u32 {
         self.state
     }
+
+    fn update_zeros(&mut self, len: u64) {
+        self.state = Self::combiner(len).append_zeros(self.state);
+    }
+}
+
+impl Hasher for Crc32C {
+    /// Returns the crc spread over all 64 bits, as hash maps use the top bits of the hash
+    fn finish(&self) -> u64 {
+        super::spread_hash(self.digest())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+}
+
+impl Default for Crc32C {
+    /// Creates a new `Crc32C` using the simd approach when the `hardware` feature is enabled and the
+    /// table lookup approach otherwise
+    fn default() -> Self {
+        #[cfg(feature = "hardware")]
+        return Self::new_simd();
+        #[cfg(not(feature = "hardware"))]
+        return Self::new_lookup();
+    }
+}
+
+/// Builds [Crc32C] hashers for use in hash maps. The backend is selected once when the builder is
+/// created, using the simd approach when the `hardware` feature is enabled and the table lookup
+/// approach otherwise.
+#[derive(Clone)]
+pub struct Crc32CBuildHasher {
+    hasher: Crc32C,
+}
+
+impl Crc32CBuildHasher {
+    /// Creates a new `Crc32CBuildHasher`
+    pub fn new() -> Self {
+        Self {
+            hasher: Crc32C::default(),
+        }
+    }
+}
+
+impl Default for Crc32CBuildHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for Crc32CBuildHasher {
+    type Hasher = Crc32C;
+
+    fn build_hasher(&self) -> Crc32C {
+        self.hasher.clone()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{assert_forge, assert_patch, assert_update_zeros};
 
     static EMPTY_DATA: &[u8; 0] = b"";
     static SMALL_DATA_1: &[u8; 9] = b"123456789";
@@ -185,6 +494,17 @@ mod tests {
         test_hardware(LARGE_DATA_2, LARGE_DATA_2_CRC32);
     }
 
+    #[test]
+    #[cfg(feature = "hardware")]
+    fn test_crc32c_hardware_interleaved() {
+        let data = crate::test_util::pseudo_random_data(20_000);
+        for len in [767, 768, 769, 1543, 6143, 6144, 6151, 6912, 13_057, 20_000] {
+            let mut lookup = Crc32C::new_lookup();
+            lookup.update(&data[..len]);
+            test_hardware(&data[..len], lookup.digest());
+        }
+    }
+
     #[cfg(feature = "hardware")]
     fn test_simd(data: &[u8], expected_crc: u32) {
         let mut crc = Crc32C::new_simd();
@@ -201,4 +521,119 @@ mod tests {
         test_simd(LARGE_DATA_1, LARGE_DATA_1_CRC32);
         test_simd(LARGE_DATA_2, LARGE_DATA_2_CRC32);
     }
+
+    #[test]
+    fn test_crc32c_backend() {
+        assert_eq!(Crc32C::new_naive().selected_backend(), Backend::Naive);
+        assert_eq!(Crc32C::new_lookup().selected_backend(), Backend::Lookup);
+
+        #[cfg(all(feature = "hardware", any(target_arch = "x86", target_arch = "x86_64")))]
+        if x86_feature_detected!("sse4.2") {
+            assert_eq!(Crc32C::new_hardware().selected_backend(), Backend::Hardware);
+        }
+        #[cfg(feature = "hardware")]
+        assert_eq!(
+            Crc32C::new_simd().selected_backend(),
+            Crc32C::supported_backends().last().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_crc32c_new_with_backend() {
+        for backend in Backend::ALL {
+            match Crc32C::new_with_backend(backend) {
+                Ok(mut crc) => {
+                    assert!(Crc32C::supported_backends().any(|b| b == backend));
+                    assert_eq!(crc.selected_backend(), backend);
+                    crc.update(LARGE_DATA_2);
+                    assert_eq!(crc.digest(), LARGE_DATA_2_CRC32);
+                }
+                Err(err) => {
+                    assert!(Crc32C::supported_backends().all(|b| b != backend));
+                    assert_eq!(err, UnsupportedBackend(backend));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_crc32c_combine() {
+        for split in [0, 1, 9, 100, LARGE_DATA_2.len()] {
+            let (a, b) = LARGE_DATA_2.split_at(split);
+            let mut crc_a = Crc32C::new_lookup();
+            crc_a.update(a);
+            let mut crc_b = Crc32C::new_lookup();
+            crc_b.update(b);
+            let len_b = b.len() as u64;
+            assert_eq!(
+                Crc32C::combine(crc_a.digest(), crc_b.digest(), len_b),
+                LARGE_DATA_2_CRC32
+            );
+            assert_eq!(
+                Crc32C::combiner(len_b).combine(crc_a.digest(), crc_b.digest()),
+                LARGE_DATA_2_CRC32
+            );
+        }
+    }
+
+    #[test]
+    fn test_crc32c_hasher() {
+        let build_hasher = Crc32CBuildHasher::new();
+        let mut hasher = build_hasher.build_hasher();
+        hasher.write(SMALL_DATA_1);
+        assert_eq!(
+            hasher.finish(),
+            crate::check32::spread_hash(SMALL_DATA_1_CRC32)
+        );
+        assert_eq!(
+            build_hasher.hash_one(SMALL_DATA_1),
+            build_hasher.hash_one(SMALL_DATA_1)
+        );
+
+        // the top 7 bits, used as control bytes by hashbrown, differ between keys
+        let tags: std::collections::HashSet<u64> =
+            (0..64u32).map(|i| build_hasher.hash_one(i) >> 57).collect();
+        assert!(tags.len() > 16);
+
+        let mut map = std::collections::HashMap::with_hasher(build_hasher);
+        map.insert(&SMALL_DATA_1[..], 1);
+        map.insert(&SMALL_DATA_2[..], 2);
+        assert_eq!(map.get(&SMALL_DATA_1[..]), Some(&1));
+        assert_eq!(map.get(&SMALL_DATA_2[..]), Some(&2));
+    }
+
+    #[test]
+    fn test_crc32c_const_checksum() {
+        const SMALL_DATA_1_CONST_CRC32: u32 = Crc32C::const_checksum(SMALL_DATA_1);
+        assert_eq!(SMALL_DATA_1_CONST_CRC32, SMALL_DATA_1_CRC32);
+        assert_eq!(Crc32C::const_checksum(EMPTY_DATA), EMPTY_DATA_CRC32);
+        assert_eq!(Crc32C::const_checksum(SMALL_DATA_2), SMALL_DATA_2_CRC32);
+        assert_eq!(Crc32C::const_checksum(LARGE_DATA_1), LARGE_DATA_1_CRC32);
+        assert_eq!(Crc32C::const_checksum(LARGE_DATA_2), LARGE_DATA_2_CRC32);
+    }
+
+    #[test]
+    fn test_crc32c_update_zeros() {
+        for backend in Crc32C::supported_backends() {
+            assert_update_zeros(Crc32C::new_with_backend(backend).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_crc32c_patch() {
+        for backend in Crc32C::supported_backends() {
+            let crc = Crc32C::new_with_backend(backend).unwrap();
+            assert_patch(&crc, |old_crc, total_len, offset, old, new| {
+                crc.patch(old_crc, total_len, offset, old, new)
+            });
+        }
+    }
+
+    #[test]
+    fn test_crc32c_forge() {
+        for backend in Crc32C::supported_backends() {
+            let crc = Crc32C::new_with_backend(backend).unwrap();
+            assert_forge(&crc, |data, offset, target| crc.forge(data, offset, target));
+        }
+    }
 }
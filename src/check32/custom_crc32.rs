@@ -1,304 +1,318 @@
 #[cfg(feature = "hardware")]
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-use crate::check32::platform::x86::compute_crc;
+use crate::check32::platform::x86::{compute_crc, compute_crc_msb};
 
 #[cfg(feature = "hardware")]
-#[cfg(target_arch = "aarch64")]
-use crate::check32::platform::arm::compute_crc;
+#[cfg(target_arch = "x86_64")]
+use crate::check32::platform::x86::{compute_crc_avx2, compute_crc_avx512};
 
 #[cfg(feature = "hardware")]
 #[cfg(target_arch = "aarch64")]
-use std::arch::is_aarch64_feature_detected;
+use crate::check32::platform::arm::{compute_crc, compute_crc_msb};
 
-use crate::check32::Crc32Digest;
+use crate::backend::Backend;
+use crate::crc::sealed::SimdKernels;
+#[cfg(feature = "hardware")]
+use crate::crc::sealed::Width;
+#[cfg(feature = "hardware")]
+use crate::crc::CrcWidth;
+use crate::crc::{ComputeFn, Crc};
 
-pub struct CustomCrc32 {
-    polynomial_u32: u32,
-    rev_polynomial_u64: u64,
-    simd_constants: [u64; 7],
-    lookup_table: [[u32; 256]; 16],
-    compute: fn(&mut Self, &[u8]),
-    state: u32,
+/// Crc32 for any polynomial and crc parameters, see [Crc] for the constructors
+pub type CustomCrc32 = Crc<u32>;
+
+/// Constants used by the crc32 simd kernels
+#[cfg(feature = "hardware")]
+#[derive(Clone, Copy)]
+pub struct SimdConstants {
+    constants: [u64; 7],
+    wide_constants: [u64; 6],
+    polynomial: u32,
+    rev_polynomial: u64,
 }
 
-impl CustomCrc32 {
-    /// Creates a new `CustomCrc32` using naive approach
-    pub fn new_naive(polynomial: u32) -> Self {
-        let polynomial_u32 = polynomial;
-        let polynomial_u64 = polynomial_u32 as u64 & 0x1_FFFF_FFFF;
-        let rev_polynomial_u64 = Self::reverse_constant(polynomial_u64);
-        let simd_constants = Self::generate_simd_reflected_constants(polynomial_u64);
-        let lookup_table = Self::generate_lookup_table_16(polynomial_u32);
+#[cfg(feature = "hardware")]
+impl SimdConstants {
+    /// Generates the constants for a crc of `width` bits, up to 32.
+    ///
+    /// The crc of width w with polynomial P is the top w bits of the crc32 with polynomial
+    /// P * x^(32 - w), or the low w bits when reflected, so narrower crcs run the crc32 kernels
+    /// on the shifted polynomial.
+    pub(crate) const fn new(polynomial: u32, width: u8, refin: bool) -> Self {
+        let polynomial = polynomial << (32 - width as u32);
+        let constants = if refin {
+            CustomCrc32::generate_simd_reflected_constants(polynomial)
+        } else {
+            CustomCrc32::generate_simd_constants(polynomial)
+        };
         Self {
-            polynomial_u32,
-            rev_polynomial_u64,
-            simd_constants,
-            state: 0,
-            compute: Self::compute_naive,
-            lookup_table,
+            constants,
+            wide_constants: CustomCrc32::generate_simd_wide_reflected_constants(polynomial),
+            polynomial,
+            rev_polynomial: CustomCrc32::reverse_constant(polynomial),
         }
     }
+}
 
-    /// Creates a new `CustomCrc32` using a table lookup approach
-    pub fn new_lookup(polynomial: u32) -> Self {
-        let mut crc = Self::new_naive(polynomial);
-        crc.compute = Self::compute_lookup;
-        crc
-    }
+/// The crc32 simd kernels, used for every crc of up to 32 bits
+#[cfg(feature = "hardware")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SimdKernel {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    Reflected,
+    #[cfg(target_arch = "x86_64")]
+    ReflectedAvx2,
+    #[cfg(target_arch = "x86_64")]
+    ReflectedAvx512,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    Msb,
+}
 
-    #[cfg(feature = "hardware")]
-    /// Creates a new `CustomCrc32` using simd intrinsics based on
-    /// [intel's paper](https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/fast-crc-computation-generic-polynomials-pclmulqdq-paper.pdf)
-    /// - x86 and x86_64 requires the cpu features sse4.2, pclmulqdq
-    /// - aarch64 requires the cpu features neon, aes
-    /// - Otherwise defaults to using hardware crc intrinsics
-    pub fn new_simd(polynomial: u64) -> Self {
-        let mut crc = Self::new_naive(polynomial as u32);
-        crc.compute = Self::compute_simd;
-        crc
+#[cfg(feature = "hardware")]
+impl SimdKernel {
+    /// Returns the kernel of a simd backend for the bit order, if the cpu supports it. The wide
+    /// simd kernels only support reflected polynomials.
+    pub(crate) fn select(refin: bool, backend: Backend) -> Option<Self> {
+        if !CustomCrc32::is_simd_supported(backend) {
+            return None;
+        }
+        match (backend, refin) {
+            #[cfg(target_arch = "x86_64")]
+            (Backend::SimdAvx512, true) => Some(Self::ReflectedAvx512),
+            #[cfg(target_arch = "x86_64")]
+            (Backend::SimdAvx2, true) => Some(Self::ReflectedAvx2),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+            (Backend::SimdSse | Backend::SimdNeon, true) => Some(Self::Reflected),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+            (Backend::SimdSse | Backend::SimdNeon, false) => Some(Self::Msb),
+            _ => None,
+        }
     }
 
-    fn compute_naive(&mut self, data: &[u8]) {
-        self.state = Self::crc32_naive(self.state, self.polynomial_u32, data);
+    /// Updates the crc with the kernel, and the table lookup approach for the remaining bytes.
+    /// `prev_crc` is the complemented register used by every kernel, and is widened to the crc32
+    /// register the kernels run on.
+    ///
+    /// # Safety
+    /// The cpu has to support the features required by the kernel, see [select](Self::select)
+    pub(crate) unsafe fn update<W: CrcWidth>(
+        self,
+        prev_crc: W,
+        simd: &SimdConstants,
+        lookup_table: &[[W; 256]; 16],
+        data: &[u8],
+    ) -> W {
+        let shift = 32 - W::BITS as u32;
+        // the register bits below a msb first crc or above a reflected crc are zero, so their
+        // complement is all ones
+        let reflected_crc = (u64::MAX << W::BITS | prev_crc.into()) as u32;
+        let msb_crc = (prev_crc.into() << shift) as u32 | ((1u64 << shift) - 1) as u32;
+        match self {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+            Self::Reflected => {
+                let (crc, data) =
+                    compute_crc(reflected_crc, simd.constants, simd.rev_polynomial, data);
+                W::lookup(W::from_u64(crc as u64), lookup_table, data)
+            }
+            #[cfg(target_arch = "x86_64")]
+            Self::ReflectedAvx2 => {
+                let (crc, data) = compute_crc_avx2(
+                    reflected_crc,
+                    simd.constants,
+                    simd.wide_constants,
+                    simd.rev_polynomial,
+                    data,
+                );
+                W::lookup(W::from_u64(crc as u64), lookup_table, data)
+            }
+            #[cfg(target_arch = "x86_64")]
+            Self::ReflectedAvx512 => {
+                let (crc, data) = compute_crc_avx512(
+                    reflected_crc,
+                    simd.constants,
+                    simd.wide_constants,
+                    simd.rev_polynomial,
+                    data,
+                );
+                W::lookup(W::from_u64(crc as u64), lookup_table, data)
+            }
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+            Self::Msb => {
+                let (crc, data) =
+                    compute_crc_msb(msb_crc, simd.constants, simd.polynomial as u64, data);
+                W::msb_lookup(W::from_u64((crc >> shift) as u64), lookup_table, data)
+            }
+        }
     }
+}
 
-    fn compute_lookup(&mut self, data: &[u8]) {
-        self.state = Self::crc32_lookup(self.state, &self.lookup_table, data);
-    }
+/// Selects the simd kernel of a [Crc] of up to 32 bits
+#[cfg(feature = "hardware")]
+pub(crate) fn select_simd<W: CrcWidth + Width<SimdConstants = SimdConstants>>(
+    refin: bool,
+    backend: Backend,
+) -> Option<ComputeFn<W>> {
+    // safety: the kernels are only selected when the cpu supports them
+    let compute: ComputeFn<W> = match SimdKernel::select(refin, backend)? {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+        SimdKernel::Reflected => |crc, prev_crc, data| unsafe {
+            SimdKernel::Reflected.update(prev_crc, &crc.simd_constants, &crc.lookup_table, data)
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdKernel::ReflectedAvx2 => |crc, prev_crc, data| unsafe {
+            SimdKernel::ReflectedAvx2.update(prev_crc, &crc.simd_constants, &crc.lookup_table, data)
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdKernel::ReflectedAvx512 => |crc, prev_crc, data| unsafe {
+            SimdKernel::ReflectedAvx512.update(
+                prev_crc,
+                &crc.simd_constants,
+                &crc.lookup_table,
+                data,
+            )
+        },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+        SimdKernel::Msb => |crc, prev_crc, data| unsafe {
+            SimdKernel::Msb.update(prev_crc, &crc.simd_constants, &crc.lookup_table, data)
+        },
+    };
+    Some(compute)
+}
 
-    fn compute_simd(&mut self, mut data: &[u8]) {
-        (self.state, data) = Self::crc32_simd(
-            self.state,
-            self.simd_constants,
-            self.rev_polynomial_u64,
-            data,
-        );
-        self.state = Self::crc32_lookup(self.state, &self.lookup_table, data);
+impl SimdKernels for u32 {
+    #[cfg(feature = "hardware")]
+    type Constants = SimdConstants;
+    #[cfg(not(feature = "hardware"))]
+    type Constants = ();
+
+    #[cfg(feature = "hardware")]
+    fn constants(polynomial: u32, refin: bool) -> SimdConstants {
+        SimdConstants::new(polynomial, 32, refin)
     }
 
-    pub(crate) const fn crc32_naive(prev_crc: u32, polynomial: u32, data: &[u8]) -> u32 {
-        let mut crc = !prev_crc;
-        let polynomial = polynomial.reverse_bits();
-        let mut i = 0;
-        let mut j = 0;
-        while i < data.len() {
-            crc ^= data[i] as u32;
-
-            while j < 8 {
-                if crc & 1u32 == 1u32 {
-                    crc = crc >> 1 ^ polynomial;
-                } else {
-                    crc >>= 1;
-                }
-                j += 1;
-            }
-            j = 0;
-            i += 1;
-        }
+    #[cfg(not(feature = "hardware"))]
+    fn constants(_polynomial: u32, _refin: bool) {}
 
-        !crc
+    #[cfg(feature = "hardware")]
+    fn select(refin: bool, backend: Backend) -> Option<ComputeFn<u32>> {
+        select_simd(refin, backend)
     }
 
-    pub(crate) fn crc32_lookup(
-        prev_crc: u32,
-        lookup_table: &[[u32; 256]; 16],
-        mut data: &[u8],
-    ) -> u32 {
-        let mut crc: u32 = !prev_crc;
-
-        while data.len() >= 16 {
-            //crc ^= u32::from_le_bytes(data[..4].try_into().unwrap());
-            crc = lookup_table[0][data[15] as usize]
-                ^ lookup_table[1][data[14] as usize]
-                ^ lookup_table[2][data[13] as usize]
-                ^ lookup_table[3][data[12] as usize]
-                ^ lookup_table[4][data[11] as usize]
-                ^ lookup_table[5][data[10] as usize]
-                ^ lookup_table[6][data[9] as usize]
-                ^ lookup_table[7][data[8] as usize]
-                ^ lookup_table[8][data[7] as usize]
-                ^ lookup_table[9][data[6] as usize]
-                ^ lookup_table[10][data[5] as usize]
-                ^ lookup_table[11][data[4] as usize]
-                ^ lookup_table[12][data[3] as usize ^ ((crc >> 24) & 0xFF) as usize]
-                ^ lookup_table[13][data[2] as usize ^ ((crc >> 16) & 0xFF) as usize]
-                ^ lookup_table[14][data[1] as usize ^ ((crc >> 8) & 0xFF) as usize]
-                ^ lookup_table[15][data[0] as usize ^ ((crc) & 0xFF) as usize];
-            data = &data[16..];
-        }
-        for &b in data {
-            crc = lookup_table[0][((crc as u8) ^ b) as usize] ^ (crc >> 8);
-        }
+    #[cfg(not(feature = "hardware"))]
+    fn select(_refin: bool, _backend: Backend) -> Option<ComputeFn<u32>> {
+        None
+    }
+}
 
-        !crc
+impl CustomCrc32 {
+    /// Returns the 4 bytes which, written over `data[offset..offset + 4]`, make the crc of `data`
+    /// equal to `target`, or `None` if no such bytes exist, which only happens for polynomials
+    /// without the x^0 term.
+    ///
+    /// # Panics
+    /// Panics if `offset + 4` exceeds the length of `data`.
+    pub fn forge(&self, data: &[u8], offset: usize, target: u32) -> Option<[u8; 4]> {
+        let bytes = self.forge_bytes(data, offset, target)?;
+        Some((bytes as u32).to_le_bytes())
     }
+}
 
-    #[cfg(feature = "hardware")]
-    pub(super) fn crc32_simd(
-        mut prev_crc: u32,
-        constants: [u64; 7],
-        rev_polynomial: u64,
-        mut data: &[u8],
-    ) -> (u32, &[u8]) {
-        unsafe {
+#[cfg(feature = "hardware")]
+impl CustomCrc32 {
+    /// Returns whether the cpu supports the features required by a simd backend
+    pub(crate) fn is_simd_supported(backend: Backend) -> bool {
+        match backend {
             #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-            if is_x86_feature_detected!("sse4.2") && is_x86_feature_detected!("pclmulqdq") {
-                (prev_crc, data) = compute_crc(prev_crc, constants, rev_polynomial, data);
+            Backend::SimdSse => {
+                x86_feature_detected!("sse4.2", "pclmulqdq")
             }
-            #[cfg(target_arch = "aarch64")]
-            if is_aarch64_feature_detected!("neon") && is_aarch64_feature_detected!("aes") {
-                (prev_crc, data) = compute_crc(prev_crc, constants, rev_polynomial, data);
+            #[cfg(target_arch = "x86_64")]
+            Backend::SimdAvx2 => {
+                x86_feature_detected!("sse4.2", "pclmulqdq", "vpclmulqdq", "avx2")
             }
-        }
-        (prev_crc, data)
-    }
-
-    pub(super) const fn generate_lookup_table_16(polynomial: u32) -> [[u32; 256]; 16] {
-        let mut table = [[0; 256]; 16];
-
-        table[0] = Self::generate_lookup_table(polynomial);
-        let mut length = 0;
-        let mut j = 1;
-
-        while length < 256 {
-            let mut crc = table[0][length];
-            while j < 16 {
-                crc = (crc >> 8) ^ table[0][crc as u8 as usize];
-                table[j][length] = crc;
-                j += 1;
+            #[cfg(target_arch = "x86_64")]
+            Backend::SimdAvx512 => {
+                x86_feature_detected!("sse4.2", "pclmulqdq", "vpclmulqdq", "avx512f")
             }
-            j = 1;
-            length += 1;
-        }
-
-        table
-    }
-
-    pub(super) const fn generate_lookup_table(mut polynomial: u32) -> [u32; 256] {
-        let mut table = [0; 256];
-        polynomial = polynomial.reverse_bits();
-        let mut length = 0;
-        let mut crc;
-        let mut j = 0;
-
-        while length < 256 {
-            crc = length;
-            while j < 8 {
-                if crc & 1u32 == 1u32 {
-                    crc = (crc >> 1) ^ polynomial;
-                } else {
-                    crc >>= 1;
-                }
-                j += 1;
+            #[cfg(target_arch = "aarch64")]
+            Backend::SimdNeon => {
+                aarch64_feature_detected!("neon", "aes")
             }
-            table[length as usize] = crc;
-            j = 0;
-            length += 1;
+            _ => false,
         }
-
-        table
-    }
-
-    pub(super) const fn generate_simd_constants(polynomial: u64) -> [u64; 7] {
-        let x32 = Self::division(0x100000000, polynomial).1;
-        let x64 = Self::division(Self::carry_less_mul(x32, x32), polynomial).1;
-        let x96 = Self::division(Self::carry_less_mul(x64, x32), polynomial).1;
-        let x128 = Self::division(Self::carry_less_mul(x64, x64), polynomial).1;
-        let x192 = Self::division(Self::carry_less_mul(x128, x64), polynomial).1;
-        let x256 = Self::division(Self::carry_less_mul(x128, x128), polynomial).1;
-        let x512 = Self::division(Self::carry_less_mul(x256, x256), polynomial).1;
-        let x576 = Self::division(Self::carry_less_mul(x512, x64), polynomial).1;
-
-        let u = 0x1_0000_0000 | Self::division(polynomial << 32, polynomial).0;
-
-        [x576, x512, x192, x128, x96, x64, u]
     }
 
-    pub(super) const fn generate_simd_reflected_constants(polynomial: u64) -> [u64; 7] {
-        // x32
-        let x32 = Self::division(1 << 32, polynomial).1;
-
-        //x64
-        let x64 = Self::division(Self::carry_less_mul(x32, x32), polynomial).1;
-
-        //x(128 - 32) and x(128 + 32)
-        let x96 = Self::division(Self::carry_less_mul(x64, x32), polynomial).1;
-        let x160 = Self::division(Self::carry_less_mul(x96, x64), polynomial).1;
-
-        //x(4*128 - 32) and x(4*128 + 32)
-        let x224 = Self::division(Self::carry_less_mul(x160, x64), polynomial).1;
-        let x256 = Self::division(Self::carry_less_mul(x224, x32), polynomial).1;
-        let x480 = Self::division(Self::carry_less_mul(x256, x224), polynomial).1;
-        let x544 = Self::division(Self::carry_less_mul(x480, x64), polynomial).1;
-
-        let u = 0x1_0000_0000 | Self::division(polynomial << 32, polynomial).0;
-
-        // [k1', k2', k3', k4', k5', k6', u']
+    /// Generates `[x576, x512, x192, x128, x96, x64, u]` where `xn = x^n mod polynomial` and `u`
+    /// is the barrett reduction constant `x^64 / polynomial`
+    pub(crate) const fn generate_simd_constants(polynomial: u32) -> [u64; 7] {
+        let distances = [576, 512, 192, 128, 96, 64];
         let mut constants = [0; 7];
-        constants[0] = Self::reverse_constant(x544);
-        constants[1] = Self::reverse_constant(x480);
-        constants[2] = Self::reverse_constant(x160);
-        constants[3] = Self::reverse_constant(x96);
-        constants[4] = Self::reverse_constant(x64);
-        constants[5] = Self::reverse_constant(x32);
-
-        constants[6] = Self::reverse_constant(u);
-
-        constants
-    }
-
-    const fn reverse_constant(mut constant: u64) -> u64 {
-        let mut reversed_constant = 0;
-
-        let mut count = 0;
-
-        while count < 33 {
-            reversed_constant = (reversed_constant << 1) ^ (constant & 1);
-            constant >>= 1;
-            count += 1;
+        let mut i = 0;
+        while i < distances.len() {
+            constants[i] = Self::xn_mod(distances[i], polynomial) as u64;
+            i += 1;
         }
-        reversed_constant
+        constants[6] = 1 << 32 | Self::barrett_quotient(polynomial) as u64;
+        constants
     }
 
-    const fn division(dividend: u64, polynomial: u64) -> (u64, u64) {
-        let mut remainder = dividend;
-        let mut quotient = 0;
-        let mut count = 0;
-        while count < 32 {
-            let msb = remainder >> 63;
-            quotient = (quotient << 1) ^ msb;
-            remainder = (remainder << 1) ^ (msb * (polynomial << 32));
-            count += 1;
+    /// Generates the 33 bit reflected `[k1', k2', k3', k4', k5', k6', u']`, which are
+    /// `[x544, x480, x160, x96, x64, x32, u]` where `xn = x^n mod polynomial` and `u` is the
+    /// barrett reduction constant `x^64 / polynomial`
+    pub(crate) const fn generate_simd_reflected_constants(polynomial: u32) -> [u64; 7] {
+        let distances = [544, 480, 160, 96, 64, 32];
+        let mut constants = [0; 7];
+        let mut i = 0;
+        while i < distances.len() {
+            constants[i] = Self::reverse_constant(Self::xn_mod(distances[i], polynomial));
+            i += 1;
         }
-        (quotient, remainder >> 32)
+        constants[6] = Self::reverse_constant(Self::barrett_quotient(polynomial)) | 1;
+        constants
     }
 
-    const fn carry_less_mul(a: u64, b: u64) -> u64 {
-        let mut result = 0;
-        let mut count = 0;
-        while count < 32 {
-            if (b >> count) & 1 == 1 {
-                result ^= a << count
-            }
-            count += 1;
+    /// Generates the reflected constants for folding 512 bit and 256 bit registers, which are
+    /// `[k(2048 + 32), k(2048 - 32), k(1024 + 32), k(1024 - 32), k(256 + 32), k(256 - 32)]`
+    /// where `k(n) = x^n mod polynomial`
+    pub(crate) const fn generate_simd_wide_reflected_constants(polynomial: u32) -> [u64; 6] {
+        let mut constants = [0; 6];
+        let distances = [2048, 1024, 256];
+        let mut i = 0;
+        while i < distances.len() {
+            constants[2 * i] = Self::reverse_constant(Self::xn_mod(distances[i] + 32, polynomial));
+            constants[2 * i + 1] =
+                Self::reverse_constant(Self::xn_mod(distances[i] - 32, polynomial));
+            i += 1;
         }
-        result
+        constants
     }
-}
 
-impl Crc32Digest for CustomCrc32 {
-    fn update(&mut self, data: &[u8]) {
-        (self.compute)(self, data)
+    /// Reflects a 32 bit constant into the 33 bit reflected form used by the simd kernels, the
+    /// implicit x^32 term maps to bit 0 and is left to the caller
+    const fn reverse_constant(constant: u32) -> u64 {
+        (constant.reverse_bits() as u64) << 1
     }
 
-    fn digest(&self) -> u32 {
-        self.state
+    /// Generates the reflected constants used by the interleaved hardware crc kernels to shift a
+    /// crc register over `stripe` and `2 * stripe` bytes.
+    ///
+    /// A carry less multiplication of two 32 bit reflected values followed by a crc32 instruction
+    /// over the 64 bit product multiplies by an extra x^33, so the constants are x^(8n - 33).
+    pub(crate) const fn generate_interleave_constants(polynomial: u32, stripe: usize) -> [u64; 2] {
+        let shift = 8 * stripe as u64 - 33;
+        [
+            Self::xn_mod(shift + 8 * stripe as u64, polynomial).reverse_bits() as u64,
+            Self::xn_mod(shift, polynomial).reverse_bits() as u64,
+        ]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::UnsupportedBackend;
+    use crate::crc::CrcDigest;
+    use crate::params::CrcParams;
 
     const POLYNOMIAL: u64 = 0x104C11DB7u64;
 
@@ -306,22 +320,86 @@ mod tests {
 
     const LARGE_DATA_2_CRC32: u32 = 0x7EC1A494;
 
+    const CRC32_BZIP2: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x04C11DB7,
+        init: 0xFFFFFFFF,
+        refin: false,
+        refout: false,
+        xorout: 0xFFFFFFFF,
+        check: 0xFC891918,
+        residue: 0xC704DD7B,
+    };
+
+    const CRC32_MPEG2: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x04C11DB7,
+        init: 0xFFFFFFFF,
+        refin: false,
+        refout: false,
+        xorout: 0x00000000,
+        check: 0x0376E6E7,
+        residue: 0x00000000,
+    };
+
+    const CRC32_CKSUM: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x04C11DB7,
+        init: 0x00000000,
+        refin: false,
+        refout: false,
+        xorout: 0xFFFFFFFF,
+        check: 0x765E7680,
+        residue: 0xC704DD7B,
+    };
+
+    const CRC32_JAMCRC: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x04C11DB7,
+        init: 0xFFFFFFFF,
+        refin: true,
+        refout: true,
+        xorout: 0x00000000,
+        check: 0x340BC6D9,
+        residue: 0x00000000,
+    };
+
+    const CRC32_XFER: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x000000AF,
+        init: 0x00000000,
+        refin: false,
+        refout: false,
+        xorout: 0x00000000,
+        check: 0xBD0BE338,
+        residue: 0x00000000,
+    };
+
+    const CRC32_PARAMS: [CrcParams; 5] = [
+        CRC32_BZIP2,
+        CRC32_MPEG2,
+        CRC32_CKSUM,
+        CRC32_JAMCRC,
+        CRC32_XFER,
+    ];
+
     #[test]
     fn test_crc32() {
-        assert_eq!(CustomCrc32::crc32_naive(0, POLYNOMIAL as u32, b""), 0);
+        assert_eq!(CustomCrc32::naive(0, POLYNOMIAL as u32, b""), 0);
         assert_eq!(
-            CustomCrc32::crc32_naive(0, POLYNOMIAL as u32, b"123456789"),
+            CustomCrc32::naive(0, POLYNOMIAL as u32, b"123456789"),
             0xCBF43926
         );
         assert_eq!(
-            CustomCrc32::crc32_naive(0, POLYNOMIAL as u32, b"hello-world"),
+            CustomCrc32::naive(0, POLYNOMIAL as u32, b"hello-world"),
             2983461467
         );
     }
 
     #[test]
+    #[cfg(feature = "hardware")]
     fn test_simd_constant() {
-        let constants = CustomCrc32::generate_simd_constants(POLYNOMIAL);
+        let constants = CustomCrc32::generate_simd_constants(POLYNOMIAL as u32);
 
         assert_eq!(constants[0], 0x8833794C);
         assert_eq!(constants[1], 0xE6228B11);
@@ -334,8 +412,9 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "hardware")]
     fn test_simd_reflected_constant() {
-        let constants = CustomCrc32::generate_simd_reflected_constants(POLYNOMIAL);
+        let constants = CustomCrc32::generate_simd_reflected_constants(POLYNOMIAL as u32);
 
         assert_eq!(constants[0], 0x154442bd4);
         assert_eq!(constants[1], 0x1c6e41596);
@@ -346,6 +425,22 @@ mod tests {
         assert_eq!(constants[6], 0x1F7011641);
     }
 
+    #[test]
+    #[cfg(feature = "hardware")]
+    fn test_simd_wide_reflected_constant() {
+        let constants = CustomCrc32::generate_simd_reflected_constants(POLYNOMIAL as u32);
+        let k = |n| CustomCrc32::reverse_constant(CustomCrc32::xn_mod(n, POLYNOMIAL as u32));
+
+        assert_eq!(k(512 + 32), constants[0]);
+        assert_eq!(k(512 - 32), constants[1]);
+        assert_eq!(k(128 + 32), constants[2]);
+        assert_eq!(k(128 - 32), constants[3]);
+
+        let wide_constants = CustomCrc32::generate_simd_wide_reflected_constants(POLYNOMIAL as u32);
+        assert_eq!(wide_constants[0], k(2048 + 32));
+        assert_eq!(wide_constants[5], k(256 - 32));
+    }
+
     #[test]
     fn test_custom_crc32() {
         let mut crc = CustomCrc32::new_naive(POLYNOMIAL as u32);
@@ -354,8 +449,150 @@ mod tests {
         crc = CustomCrc32::new_lookup(POLYNOMIAL as u32);
         crc.update(LARGE_DATA_2);
         assert_eq!(crc.digest(), LARGE_DATA_2_CRC32);
-        crc = CustomCrc32::new_simd(POLYNOMIAL);
-        crc.update(LARGE_DATA_2);
-        assert_eq!(crc.digest(), LARGE_DATA_2_CRC32);
+        #[cfg(feature = "hardware")]
+        {
+            crc = CustomCrc32::new_simd(POLYNOMIAL);
+            crc.update(LARGE_DATA_2);
+            assert_eq!(crc.digest(), LARGE_DATA_2_CRC32);
+        }
+    }
+
+    fn test_params_check(mut crc: CustomCrc32, params: CrcParams) {
+        crc.update(b"123456789");
+        assert_eq!(crc.digest(), params.check as u32);
+    }
+
+    fn test_params_large_data(mut crc: CustomCrc32, params: CrcParams) {
+        let mut expected = CustomCrc32::new_naive_with_params(params);
+        expected.update(LARGE_DATA_2);
+        crc.update(&LARGE_DATA_2[..7]);
+        crc.update(&LARGE_DATA_2[7..]);
+        assert_eq!(crc.digest(), expected.digest());
+    }
+
+    #[test]
+    fn test_custom_crc32_params() {
+        for params in CRC32_PARAMS {
+            test_params_check(CustomCrc32::new_naive_with_params(params), params);
+            test_params_check(CustomCrc32::new_lookup_with_params(params), params);
+            test_params_large_data(CustomCrc32::new_lookup_with_params(params), params);
+            test_params_check(CustomCrc32::with_params(params), params);
+            test_params_large_data(CustomCrc32::with_params(params), params);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hardware")]
+    fn test_custom_crc32_params_simd() {
+        for params in CRC32_PARAMS {
+            test_params_check(CustomCrc32::new_simd_with_params(params), params);
+            test_params_large_data(CustomCrc32::new_simd_with_params(params), params);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hardware")]
+    fn test_custom_crc32_simd_wide() {
+        let data = crate::test_util::pseudo_random_data(5000);
+        for len in [127, 128, 255, 256, 257, 511, 512, 1000, 1024, 4099, 5000] {
+            let mut expected = CustomCrc32::new_lookup(POLYNOMIAL as u32);
+            expected.update(&data[..len]);
+            let mut crc = CustomCrc32::new_simd(POLYNOMIAL);
+            crc.update(&data[..len]);
+            assert_eq!(crc.digest(), expected.digest());
+
+            #[cfg(target_arch = "x86_64")]
+            if x86_feature_detected!("vpclmulqdq", "avx2") {
+                let (prev_crc, rest) = unsafe {
+                    compute_crc_avx2(
+                        0,
+                        crc.simd_constants.constants,
+                        crc.simd_constants.wide_constants,
+                        crc.simd_constants.rev_polynomial,
+                        &data[..len],
+                    )
+                };
+                assert_eq!(
+                    CustomCrc32::lookup(prev_crc, &crc.lookup_table, rest),
+                    expected.digest()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_crc32_backend() {
+        for params in CRC32_PARAMS {
+            let crc = CustomCrc32::new_naive_with_params(params);
+            assert_eq!(crc.selected_backend(), Backend::Naive);
+            let crc = CustomCrc32::new_lookup_with_params(params);
+            assert_eq!(crc.selected_backend(), Backend::Lookup);
+
+            #[cfg(feature = "hardware")]
+            {
+                let crc = CustomCrc32::new_simd_with_params(params);
+                let expected = CustomCrc32::supported_backends(params).last().unwrap();
+                assert_eq!(crc.selected_backend(), expected);
+            }
+
+            for backend in Backend::ALL {
+                let supported = CustomCrc32::supported_backends(params).any(|b| b == backend);
+                match CustomCrc32::new_with_backend(params, backend) {
+                    Ok(crc) => {
+                        assert!(supported);
+                        test_params_check(crc, params);
+                    }
+                    Err(err) => {
+                        assert!(!supported);
+                        assert_eq!(err, UnsupportedBackend(backend));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_crc32_combine() {
+        for params in CRC32_PARAMS {
+            let mut expected = CustomCrc32::new_lookup_with_params(params);
+            expected.update(LARGE_DATA_2);
+            for split in [0, 1, 9, 100, LARGE_DATA_2.len()] {
+                let (a, b) = LARGE_DATA_2.split_at(split);
+                let mut crc_a = CustomCrc32::new_lookup_with_params(params);
+                crc_a.update(a);
+                let mut crc_b = CustomCrc32::new_lookup_with_params(params);
+                crc_b.update(b);
+                assert_eq!(
+                    crc_a.combine(crc_a.digest(), crc_b.digest(), b.len() as u64),
+                    expected.digest()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_crc32_forge() {
+        let mut data = *LARGE_DATA_2;
+        for params in CRC32_PARAMS {
+            for backend in CustomCrc32::supported_backends(params) {
+                let crc = CustomCrc32::new_with_backend(params, backend).unwrap();
+                for (offset, target) in [(0, 0), (100, 0xDEADBEEF), (237, params.check as u32)] {
+                    let bytes = crc.forge(&data, offset, target).unwrap();
+                    data[offset..offset + 4].copy_from_slice(&bytes);
+                    let mut forged = crc.clone();
+                    forged.update(&data);
+                    assert_eq!(forged.digest(), target);
+                }
+            }
+        }
+
+        // without the x^0 term the x^0 coefficient of the crc register can not be changed,
+        // which is the top bit of the reflected crc
+        let crc = CustomCrc32::new_lookup(0x04C11DB6);
+        let mut zeros = crc.clone();
+        zeros.update(&[0; 4]);
+        zeros.update(&data[4..]);
+        assert_eq!(crc.forge(&data, 0, zeros.digest() ^ 0x8000_0000), None);
+        assert!(crc.forge(&data, 0, zeros.digest()).is_some());
     }
 }
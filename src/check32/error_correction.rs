@@ -0,0 +1,339 @@
+use crate::check32::CustomCrc32;
+
+/// Location of a bit error found by [ErrorCorrector]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ErrorLocation {
+    /// Bit `bit`, counting from the least significant bit, of byte `byte` of the data
+    Data { byte: usize, bit: u8 },
+    /// Bit `bit`, counting from the least significant bit, of the expected crc
+    Crc { bit: u8 },
+}
+
+/// Result of locating the bit errors of a message with [ErrorCorrector::locate]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Correction {
+    /// The crc of the data matches the expected crc
+    NoError,
+    /// Flipping a single bit makes the crc match
+    Single(ErrorLocation),
+    /// Flipping two bits makes the crc match, and no single bit does
+    Double(ErrorLocation, ErrorLocation),
+    /// Several error patterns with the fewest bit errors make the crc match, as the hamming
+    /// distance of the polynomial is too low for the message length. The errors can not be
+    /// corrected.
+    Ambiguous,
+    /// No error pattern of at most the enabled number of bit errors makes the crc match
+    Uncorrectable,
+}
+
+/// Position of a single bit error in the syndrome table. Data bits are counted from the end of
+/// the data, as the syndrome only depends on the number of bytes after the error.
+#[derive(Debug, Clone, Copy)]
+enum Position {
+    Data { distance: usize, bit: u8 },
+    Crc { bit: u8 },
+}
+
+/// Locates and corrects single, and optionally double, bit errors of messages protected by a
+/// [CustomCrc32].
+///
+/// The syndrome of a message is the xor of the crc of the received data and the received crc.
+/// As the crc is affine, it only depends on the flipped bits, so the syndrome of every single bit
+/// error up to `max_len` bytes of data is precomputed. Double bit errors are found by looking up
+/// the syndrome left after removing each single bit error.
+///
+/// Correction is only unique when the hamming distance of the polynomial at the message length
+/// is at least 3 for single bit errors and 5 for double bit errors, otherwise
+/// [Correction::Ambiguous] is reported.
+///
+/// ```
+/// use librscrc::check32::{Correction, ErrorCorrector, ErrorLocation};
+/// use librscrc::prelude::*;
+///
+/// let corrector = ErrorCorrector::new(CustomCrc32::new_lookup(0x04C11DB7), 1500);
+///
+/// let mut data = *b"123456789";
+/// let mut crc = 0xCBF43926;
+/// data[4] ^= 0x10;
+/// assert_eq!(
+///     corrector.correct(&mut data, &mut crc),
+///     Correction::Single(ErrorLocation::Data { byte: 4, bit: 4 })
+/// );
+/// assert_eq!(&data, b"123456789");
+/// ```
+#[derive(Clone)]
+pub struct ErrorCorrector {
+    crc: CustomCrc32,
+    max_len: usize,
+    double_bit: bool,
+    // syndromes of every single bit error, sorted by syndrome
+    syndromes: Vec<(u32, Position)>,
+}
+
+impl ErrorCorrector {
+    /// Creates an `ErrorCorrector` for single bit errors in messages of up to `max_len` bytes,
+    /// using the polynomial and parameters of `crc`. Precomputes `8 * max_len + 32` syndromes.
+    pub fn new(crc: CustomCrc32, max_len: usize) -> Self {
+        let mut crc = crc;
+        crc.reset();
+        let params = crc.params();
+
+        let mut syndromes = Vec::with_capacity(max_len * 8 + 32);
+        for bit in 0..32 {
+            syndromes.push((1 << bit, Position::Crc { bit }));
+        }
+        // the syndrome of a bit error is the crc of the error followed by the bytes after it,
+        // with a zero init and xorout. The kernels work on the complemented register.
+        for bit in 0..8 {
+            let mut state = (crc.compute)(&crc, !0, &[1 << bit]);
+            for distance in 0..max_len {
+                let syndrome = if params.refin != params.refout {
+                    (!state).reverse_bits()
+                } else {
+                    !state
+                };
+                syndromes.push((syndrome, Position::Data { distance, bit }));
+                state = (crc.compute)(&crc, state, &[0]);
+            }
+        }
+        syndromes.sort_by_key(|&(syndrome, _)| syndrome);
+
+        Self {
+            crc,
+            max_len,
+            double_bit: false,
+            syndromes,
+        }
+    }
+
+    /// Enables or disables locating double bit errors, which are not located by default.
+    /// Locating them looks up a syndrome for every bit of the message, instead of a single one.
+    pub fn double_bit_errors(mut self, enabled: bool) -> Self {
+        self.double_bit = enabled;
+        self
+    }
+
+    /// Maximum length of the data in bytes
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Returns whether every single bit error of messages of `max_len` bytes has a distinct
+    /// syndrome, i.e. whether the hamming distance of the polynomial is at least 3 at that
+    /// length
+    pub fn corrects_single_errors(&self) -> bool {
+        self.syndromes.windows(2).all(|pair| pair[0].0 != pair[1].0)
+    }
+
+    /// Locates the bit errors of `data` given the `expected_crc` received along with it. Either
+    /// the data or the expected crc may contain the errors.
+    ///
+    /// # Panics
+    /// Panics if `data` is longer than [max_len](Self::max_len).
+    pub fn locate(&self, data: &[u8], expected_crc: u32) -> Correction {
+        assert!(
+            data.len() <= self.max_len,
+            "data is longer than the syndrome table"
+        );
+        let state = (self.crc.compute)(&self.crc, self.crc.state, data);
+        let syndrome = self.crc.digest_of(state) ^ expected_crc;
+        if syndrome == 0 {
+            return Correction::NoError;
+        }
+
+        let mut singles = self.matching(syndrome, data.len());
+        match (singles.next(), singles.next()) {
+            (Some((_, location)), None) => return Correction::Single(location),
+            (Some(_), Some(_)) => return Correction::Ambiguous,
+            _ => {}
+        }
+        if !self.double_bit {
+            return Correction::Uncorrectable;
+        }
+
+        let mut found = None;
+        for (i, &(first_syndrome, position)) in self.syndromes.iter().enumerate() {
+            let first = match self.location(position, data.len()) {
+                Some(location) => location,
+                None => continue,
+            };
+            // every pair is found from both of its bits, only count it from the first one
+            for (j, second) in self.matching(syndrome ^ first_syndrome, data.len()) {
+                if j > i {
+                    if found.is_some() {
+                        return Correction::Ambiguous;
+                    }
+                    found = Some(Correction::Double(first.min(second), first.max(second)));
+                }
+            }
+        }
+        found.unwrap_or(Correction::Uncorrectable)
+    }
+
+    /// Locates the bit errors like [locate](Self::locate), and flips the located bits of `data`
+    /// or `expected_crc` when the correction is unique
+    ///
+    /// # Panics
+    /// Panics if `data` is longer than [max_len](Self::max_len).
+    pub fn correct(&self, data: &mut [u8], expected_crc: &mut u32) -> Correction {
+        let correction = self.locate(data, *expected_crc);
+        let mut flip = |location| match location {
+            ErrorLocation::Data { byte, bit } => data[byte] ^= 1 << bit,
+            ErrorLocation::Crc { bit } => *expected_crc ^= 1 << bit,
+        };
+        match correction {
+            Correction::Single(location) => flip(location),
+            Correction::Double(first, second) => {
+                flip(first);
+                flip(second);
+            }
+            _ => {}
+        }
+        correction
+    }
+
+    /// Returns the indices and locations of the single bit errors with the given syndrome which
+    /// fall within data of `len` bytes
+    fn matching(
+        &self,
+        syndrome: u32,
+        len: usize,
+    ) -> impl Iterator<Item = (usize, ErrorLocation)> + '_ {
+        let start = self.syndromes.partition_point(|&(s, _)| s < syndrome);
+        self.syndromes[start..]
+            .iter()
+            .take_while(move |&&(s, _)| s == syndrome)
+            .enumerate()
+            .filter_map(move |(i, &(_, position))| Some((start + i, self.location(position, len)?)))
+    }
+
+    fn location(&self, position: Position, len: usize) -> Option<ErrorLocation> {
+        match position {
+            Position::Data { distance, bit } if distance < len => Some(ErrorLocation::Data {
+                byte: len - 1 - distance,
+                bit,
+            }),
+            Position::Data { .. } => None,
+            Position::Crc { bit } => Some(ErrorLocation::Crc { bit }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogue::CRC_32_BZIP2;
+    use crate::crc::CrcDigest;
+
+    static LARGE_DATA_2: &[u8; 241] = include_bytes!("../../sample_files/test_data_odd_size.txt");
+
+    fn checksum(mut crc: CustomCrc32, data: &[u8]) -> u32 {
+        crc.reset();
+        crc.update(data);
+        crc.digest()
+    }
+
+    fn flip(data: &mut [u8], crc: &mut u32, location: ErrorLocation) {
+        match location {
+            ErrorLocation::Data { byte, bit } => data[byte] ^= 1 << bit,
+            ErrorLocation::Crc { bit } => *crc ^= 1 << bit,
+        }
+    }
+
+    fn locations(len: usize) -> Vec<ErrorLocation> {
+        let mut locations: Vec<ErrorLocation> = (0..len * 8)
+            .step_by(7)
+            .map(|i| ErrorLocation::Data {
+                byte: i / 8,
+                bit: (i % 8) as u8,
+            })
+            .collect();
+        locations.extend((0..32).step_by(5).map(|bit| ErrorLocation::Crc { bit }));
+        locations.push(ErrorLocation::Data {
+            byte: len - 1,
+            bit: 7,
+        });
+        locations
+    }
+
+    #[test]
+    fn test_single_bit_errors() {
+        for crc in [
+            CustomCrc32::new_lookup(0x04C11DB7),
+            CustomCrc32::new_lookup(0x1EDC6F41),
+            CustomCrc32::new_naive_with_params(CRC_32_BZIP2),
+        ] {
+            let corrector = ErrorCorrector::new(crc.clone(), 1000);
+            assert!(corrector.corrects_single_errors());
+            let expected = checksum(crc.clone(), LARGE_DATA_2);
+            assert_eq!(
+                corrector.locate(LARGE_DATA_2, expected),
+                Correction::NoError
+            );
+
+            for location in locations(LARGE_DATA_2.len()) {
+                let (mut data, mut crc) = (*LARGE_DATA_2, expected);
+                flip(&mut data, &mut crc, location);
+                assert_eq!(
+                    corrector.correct(&mut data, &mut crc),
+                    Correction::Single(location)
+                );
+                assert_eq!((&data, crc), (LARGE_DATA_2, expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_double_bit_errors() {
+        // crc-32 has a hamming distance of 5 up to 2974 bits
+        let crc = CustomCrc32::new_lookup(0x04C11DB7);
+        let corrector = ErrorCorrector::new(crc.clone(), 300).double_bit_errors(true);
+        let expected = checksum(crc, LARGE_DATA_2);
+        let locations = locations(LARGE_DATA_2.len());
+        for (i, &first) in locations.iter().enumerate().step_by(17) {
+            for &second in &locations[i + 1..] {
+                let (mut data, mut crc) = (*LARGE_DATA_2, expected);
+                flip(&mut data, &mut crc, first);
+                flip(&mut data, &mut crc, second);
+                assert_eq!(
+                    corrector.correct(&mut data, &mut crc),
+                    Correction::Double(first.min(second), first.max(second))
+                );
+                assert_eq!((&data, crc), (LARGE_DATA_2, expected));
+            }
+        }
+
+        let single_only = corrector.clone().double_bit_errors(false);
+        let mut data = *LARGE_DATA_2;
+        data[0] ^= 1;
+        data[100] ^= 1;
+        assert_eq!(
+            single_only.locate(&data, expected),
+            Correction::Uncorrectable
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_errors() {
+        // x^32 + 1 only has a period of 32 bits, so bits 4 bytes apart have the same syndrome
+        let crc = CustomCrc32::new_lookup(0x00000001);
+        let corrector = ErrorCorrector::new(crc.clone(), 100);
+        assert!(!corrector.corrects_single_errors());
+
+        let mut data = LARGE_DATA_2[..100].to_vec();
+        let mut expected = checksum(crc, &data);
+        data[50] ^= 0x08;
+        assert_eq!(
+            corrector.correct(&mut data, &mut expected),
+            Correction::Ambiguous
+        );
+        assert_eq!(data[50], LARGE_DATA_2[50] ^ 0x08);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_data_too_long() {
+        let corrector = ErrorCorrector::new(CustomCrc32::new_lookup(0x04C11DB7), 100);
+        corrector.locate(LARGE_DATA_2, 0);
+    }
+}
@@ -1,24 +1,46 @@
 type UpdateFn = fn(u32, &[u8]) -> u32;
 
-pub trait Crc32Digest {
-    /// Update digest with data
-    fn update(&mut self, data: &[u8]);
-
-    /// Returns crc32 digest
-    fn digest(&self) -> u32;
+/// Spreads a crc32 over the 64 bits of a hash. Hash maps like hashbrown take their control bits
+/// from the top of the hash, which would always be zero for the crc itself. Multiplying by an odd
+/// constant keeps distinct crcs distinct.
+pub(crate) const fn spread_hash(crc: u32) -> u64 {
+    (crc as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
 }
 
+/// The digest trait of the crc32 types, kept under its previous name. It is
+/// [CrcDigest](crate::crc::CrcDigest), so importing either name brings `update` and `digest` into
+/// scope.
+///
+/// ```
+/// use librscrc::check32::{Crc32C, Crc32Digest};
+///
+/// let mut crc = Crc32C::new_lookup();
+/// crc.update(b"123456789");
+/// assert_eq!(crc.digest(), 0xE3069283);
+/// ```
+pub use crate::crc::CrcDigest as Crc32Digest;
+
+/// Combines crc32s, see [CrcCombiner]
+pub type Crc32Combiner = CrcCombiner<u32>;
+
 mod crc32;
 mod crc32c;
 mod custom_crc32;
+#[cfg(feature = "std")]
+mod error_correction;
 
 #[cfg(any(
-all(feature = "hardware", any(target_arch = "x86", target_arch = "x86_64")),
-all(feature = "hardware", any(target_arch = "aarch64"))
+    all(feature = "hardware", any(target_arch = "x86", target_arch = "x86_64")),
+    all(feature = "hardware", target_arch = "aarch64")
 ))]
-mod platform;
+pub(crate) mod platform;
 
-pub use crc32::Crc32;
-pub use crc32c::Crc32C;
-pub use custom_crc32::CustomCrc32;
+use crate::crc::CrcCombiner;
 
+pub use crc32::{Crc32, Crc32BuildHasher};
+pub use crc32c::{Crc32C, Crc32CBuildHasher};
+pub use custom_crc32::CustomCrc32;
+#[cfg(feature = "hardware")]
+pub(crate) use custom_crc32::{select_simd, SimdConstants, SimdKernel};
+#[cfg(feature = "std")]
+pub use error_correction::{Correction, ErrorCorrector, ErrorLocation};
@@ -6,11 +6,14 @@ use core::arch::aarch64::{
     uint64x2_t, vandq_u64, vdupq_n_u32, vdupq_n_u8, veorq_u64, vextq_u8, vgetq_lane_u32,
     vgetq_lane_u64, vld1q_u32, vld1q_u64, vld1q_u8, vmull_p64, vreinterpretq_u32_u64,
     vreinterpretq_u64_p128, vreinterpretq_u64_u32, vreinterpretq_u64_u8, vreinterpretq_u8_u64,
-    vsetq_lane_u32,
+    vsetq_lane_u32, vsetq_lane_u64, vshrq_n_u64,
 };
 
 #[cfg(all(feature = "hardware", target_arch = "aarch64"))]
-use std::arch::asm;
+use core::arch::asm;
+
+#[cfg(all(feature = "nightly", feature = "hardware", target_arch = "aarch64"))]
+use crate::check32::platform::{LONG_STRIPE, SHORT_STRIPE};
 
 #[cfg(all(feature = "nightly", feature = "hardware", target_arch = "aarch64"))]
 #[target_feature(enable = "crc")]
@@ -58,6 +61,60 @@ pub(crate) unsafe fn compute_crc32c_hardware_aarch64(prev_crc: u32, data: &[u8])
     !crc
 }
 
+/// Computes crc32c using three independent streams of crc instructions, which hides the latency
+/// of `__crc32cd`. The streams are recombined using carry less multiplication. `constants` are
+/// generated by `CustomCrc32::generate_interleave_constants` for `LONG_STRIPE` followed by
+/// `SHORT_STRIPE`.
+#[cfg(all(feature = "nightly", feature = "hardware", target_arch = "aarch64"))]
+#[target_feature(enable = "crc", enable = "aes")]
+pub(crate) unsafe fn compute_crc32c_hardware_interleaved_aarch64(
+    prev_crc: u32,
+    constants: [u64; 4],
+    mut data: &[u8],
+) -> u32 {
+    let mut crc = !prev_crc;
+
+    while data.len() >= 3 * LONG_STRIPE {
+        crc = crc32c_3way(crc, &data[..3 * LONG_STRIPE], constants[0], constants[1]);
+        data = &data[3 * LONG_STRIPE..];
+    }
+
+    while data.len() >= 3 * SHORT_STRIPE {
+        crc = crc32c_3way(crc, &data[..3 * SHORT_STRIPE], constants[2], constants[3]);
+        data = &data[3 * SHORT_STRIPE..];
+    }
+
+    compute_crc32c_hardware_aarch64(!crc, data)
+}
+
+#[cfg(all(feature = "nightly", feature = "hardware", target_arch = "aarch64"))]
+#[target_feature(enable = "crc", enable = "aes")]
+unsafe fn crc32c_3way(crc: u32, block: &[u8], k_2stripe: u64, k_stripe: u64) -> u32 {
+    let stripe = block.len() / 3;
+    let (a, block) = block.split_at(stripe);
+    let (b, c) = block.split_at(stripe);
+
+    let mut crc0 = crc;
+    let mut crc1 = 0;
+    let mut crc2 = 0;
+
+    for ((a, b), c) in a
+        .chunks_exact(8)
+        .zip(b.chunks_exact(8))
+        .zip(c.chunks_exact(8))
+    {
+        crc0 = __crc32cd(crc0, u64::from_le_bytes(a.try_into().unwrap()));
+        crc1 = __crc32cd(crc1, u64::from_le_bytes(b.try_into().unwrap()));
+        crc2 = __crc32cd(crc2, u64::from_le_bytes(c.try_into().unwrap()));
+    }
+
+    // shift crc0 over 2 stripes and crc1 over 1 stripe
+    crc0 = __crc32cd(0, vmull_p64(crc0 as u64, k_2stripe) as u64);
+    crc1 = __crc32cd(0, vmull_p64(crc1 as u64, k_stripe) as u64);
+
+    crc0 ^ crc1 ^ crc2
+}
+
 /// This function computes the crc values based on the implementation of chromiums zlib
 /// https://github.com/chromium/chromium/commit/a0771caebe87477558454cc6d793562e3afe74ac
 #[target_feature(enable = "neon", enable = "aes")]
@@ -130,10 +187,81 @@ pub(crate) unsafe fn compute_crc(
     (!(vgetq_lane_u32(vreinterpretq_u32_u64(x), 1)), data)
 }
 
+/// This function computes the crc values of non reflected (msb first) polynomials based on the
+/// non reflected algorithm in [intel's paper](https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/fast-crc-computation-generic-polynomials-pclmulqdq-paper.pdf).
+/// The constants are expected in the order generated by `CustomCrc32::generate_simd_constants`
+#[target_feature(enable = "neon", enable = "aes")]
+#[cfg(target_arch = "aarch64")]
+pub(crate) unsafe fn compute_crc_msb(
+    prev_crc: u32,
+    constants: [u64; 7],
+    polynomial: u64,
+    mut data: &[u8],
+) -> (u32, &[u8]) {
+    if data.len() < 128 {
+        return (prev_crc, data);
+    }
+
+    // this is safe since we already validated we have at least 128 bytes
+    let mut x3 = get_simd_128_msb(&mut data);
+    let mut x2 = get_simd_128_msb(&mut data);
+    let mut x1 = get_simd_128_msb(&mut data);
+    let mut x0 = get_simd_128_msb(&mut data);
+
+    let prev_crc_vec = vreinterpretq_u64_u32(vsetq_lane_u32(!prev_crc, vdupq_n_u32(0), 3));
+
+    x3 = veorq_u64(x3, prev_crc_vec);
+
+    let k1k2 = vld1q_u64([constants[1], constants[0]].as_ptr());
+
+    while data.len() >= 64 {
+        x3 = fold_128(x3, get_simd_128_msb(&mut data), k1k2);
+        x2 = fold_128(x2, get_simd_128_msb(&mut data), k1k2);
+        x1 = fold_128(x1, get_simd_128_msb(&mut data), k1k2);
+        x0 = fold_128(x0, get_simd_128_msb(&mut data), k1k2);
+    }
+
+    //fold into 128 bits
+    let k3k4 = vld1q_u64([constants[3], constants[2]].as_ptr());
+    let mut x = fold_128(x3, x2, k3k4);
+    x = fold_128(x, x1, k3k4);
+    x = fold_128(x, x0, k3k4);
+
+    // fold 1*128 bits
+    while data.len() >= 16 {
+        x = fold_128(x, get_simd_128_msb(&mut data), k3k4);
+    }
+
+    // fold 128 bits to 96 bits
+    let zero = vdupq_n_u8(0);
+    let k5k6 = vld1q_u64([constants[5], constants[4]].as_ptr());
+    let x2 = vreinterpretq_u8_u64(vsetq_lane_u64(0, x, 1));
+    let x2 = vreinterpretq_u64_u8(vextq_u8(zero, x2, 12));
+    x = pmull_11(x, k5k6);
+    x = veorq_u64(x, x2);
+
+    // fold 96 bits to 64 bits
+    let x2 = vsetq_lane_u64(0, x, 1);
+    x = vreinterpretq_u64_u8(vextq_u8(vreinterpretq_u8_u64(x), zero, 8));
+    x = pmull_00(x, k5k6);
+    x = veorq_u64(x, x2);
+
+    /*
+     * Barret reduce to 32-bits.
+     */
+    let pu = vld1q_u64([constants[6], polynomial].as_ptr());
+
+    let mut x2 = pmull_00(vshrq_n_u64(x, 32), pu);
+    x2 = pmull_01(vshrq_n_u64(x2, 32), pu);
+    x = veorq_u64(x, x2);
+
+    (!(vgetq_lane_u32(vreinterpretq_u32_u64(x), 0)), data)
+}
+
 /// performing the equivalent of _mm_clmulepi64_si128(a, b, 0x00);
 #[cfg(target_arch = "aarch64")]
 #[inline]
-unsafe fn pmull_01(a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
+pub(crate) unsafe fn pmull_01(a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
     //let mut xmm0 = b;
     let result = vmull_p64(vgetq_lane_u64(a, 0), vgetq_lane_u64(b, 1));
     vreinterpretq_u64_p128(result)
@@ -142,7 +270,7 @@ unsafe fn pmull_01(a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
 /// performing the equivalent of _mm_clmulepi64_si128(a, b, 0x00);
 #[cfg(target_arch = "aarch64")]
 #[inline]
-unsafe fn pmull_00(a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
+pub(crate) unsafe fn pmull_00(a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
     //let mut xmm0 = b;
     let result = vmull_p64(vgetq_lane_u64(a, 0), vgetq_lane_u64(b, 0));
     vreinterpretq_u64_p128(result)
@@ -206,7 +334,11 @@ unsafe fn pmull_11(a: uint64x2_t, b: uint64x2_t) -> uint64x2_t {
 /// fold 128 bits
 #[cfg(target_arch = "aarch64")]
 #[inline]
-unsafe fn fold_128(a: uint64x2_t, mut b: uint64x2_t, constant: uint64x2_t) -> uint64x2_t {
+pub(crate) unsafe fn fold_128(
+    a: uint64x2_t,
+    mut b: uint64x2_t,
+    constant: uint64x2_t,
+) -> uint64x2_t {
     //let mut xmm0 = b;
     let xmm1 = pmull_00(a, constant);
     let xmm2 = pmull_11(a, constant);
@@ -218,9 +350,19 @@ unsafe fn fold_128(a: uint64x2_t, mut b: uint64x2_t, constant: uint64x2_t) -> ui
 /// read 128bits
 #[cfg(target_arch = "aarch64")]
 #[inline]
-unsafe fn get_simd_128(data: &mut &[u8]) -> uint64x2_t {
+pub(crate) unsafe fn get_simd_128(data: &mut &[u8]) -> uint64x2_t {
     let x1 = u64::from_le_bytes(data[0..8].try_into().unwrap());
     let x2 = u64::from_le_bytes(data[8..16].try_into().unwrap());
     *data = &data[16..];
     vld1q_u64([x1, x2].as_ptr())
 }
+
+/// read 128bits in msb first order
+#[cfg(target_arch = "aarch64")]
+#[inline]
+unsafe fn get_simd_128_msb(data: &mut &[u8]) -> uint64x2_t {
+    let x1 = u64::from_be_bytes(data[0..8].try_into().unwrap());
+    let x2 = u64::from_be_bytes(data[8..16].try_into().unwrap());
+    *data = &data[16..];
+    vld1q_u64([x2, x1].as_ptr())
+}
@@ -1,5 +1,17 @@
+/// Number of bytes in each of the three stripes processed by the interleaved hardware crc kernels
+#[cfg(any(
+    target_arch = "x86_64",
+    all(feature = "nightly", target_arch = "aarch64")
+))]
+pub(crate) const LONG_STRIPE: usize = 2048;
+#[cfg(any(
+    target_arch = "x86_64",
+    all(feature = "nightly", target_arch = "aarch64")
+))]
+pub(crate) const SHORT_STRIPE: usize = 256;
+
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 pub mod x86;
 
-#[cfg(any(target_arch = "aarch64"))]
+#[cfg(target_arch = "aarch64")]
 pub mod arm;
@@ -1,14 +1,27 @@
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::{
     __m128i, _mm_and_si128, _mm_clmulepi64_si128, _mm_crc32_u32, _mm_crc32_u64, _mm_crc32_u8,
-    _mm_cvtsi32_si128, _mm_extract_epi32, _mm_set_epi64x, _mm_setr_epi32, _mm_srli_si128,
-    _mm_xor_si128,
+    _mm_cvtsi128_si32, _mm_cvtsi128_si64, _mm_cvtsi32_si128, _mm_cvtsi64_si128, _mm_extract_epi32,
+    _mm_move_epi64, _mm_set_epi32, _mm_set_epi64x, _mm_setr_epi32, _mm_slli_si128, _mm_srli_epi64,
+    _mm_srli_si128, _mm_xor_si128,
 };
 
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::{
+    __m256i, __m512i, _mm256_broadcastsi128_si256, _mm256_clmulepi64_epi128,
+    _mm256_extracti128_si256, _mm256_loadu_si256, _mm256_xor_si256, _mm256_zextsi128_si256,
+    _mm512_broadcast_i32x4, _mm512_clmulepi64_epi128, _mm512_extracti32x4_epi32,
+    _mm512_loadu_si512, _mm512_ternarylogic_epi64, _mm512_xor_si512, _mm512_zextsi128_si512,
+};
+
+#[cfg(target_arch = "x86_64")]
+use crate::check32::platform::{LONG_STRIPE, SHORT_STRIPE};
+
 #[cfg(target_arch = "x86")]
 use core::arch::x86::{
-    __m128i, _mm_and_si128, _mm_clmulepi64_si128, _mm_crc32_u32, _mm_crc32_u8, _mm_cvtsi32_si128,
-    _mm_extract_epi32, _mm_set_epi64x, _mm_setr_epi32, _mm_srli_si128, _mm_xor_si128,
+    __m128i, _mm_and_si128, _mm_clmulepi64_si128, _mm_crc32_u32, _mm_crc32_u8, _mm_cvtsi128_si32,
+    _mm_cvtsi32_si128, _mm_extract_epi32, _mm_move_epi64, _mm_set_epi32, _mm_set_epi64x,
+    _mm_setr_epi32, _mm_slli_si128, _mm_srli_epi64, _mm_srli_si128, _mm_xor_si128,
 };
 
 #[target_feature(enable = "sse4.2")]
@@ -34,6 +47,71 @@ pub(crate) unsafe fn compute_crc32c_hardware_x86_64(prev_crc: u32, data: &[u8])
     !crc
 }
 
+/// Computes crc32c using three independent streams of crc instructions, which hides the latency
+/// of `_mm_crc32_u64`. The streams are recombined using carry less multiplication, similar to
+/// linux's crc32c-pcl-intel. `constants` are generated by `CustomCrc32::generate_interleave_constants`
+/// for `LONG_STRIPE` followed by `SHORT_STRIPE`.
+#[target_feature(enable = "sse4.2", enable = "pclmulqdq")]
+#[cfg(target_arch = "x86_64")]
+pub(crate) unsafe fn compute_crc32c_hardware_interleaved_x86_64(
+    prev_crc: u32,
+    constants: [u64; 4],
+    mut data: &[u8],
+) -> u32 {
+    let mut crc = (!prev_crc) as u64;
+
+    while data.len() >= 3 * LONG_STRIPE {
+        crc = crc32c_3way(crc, &data[..3 * LONG_STRIPE], constants[0], constants[1]);
+        data = &data[3 * LONG_STRIPE..];
+    }
+
+    while data.len() >= 3 * SHORT_STRIPE {
+        crc = crc32c_3way(crc, &data[..3 * SHORT_STRIPE], constants[2], constants[3]);
+        data = &data[3 * SHORT_STRIPE..];
+    }
+
+    compute_crc32c_hardware_x86_64(!(crc as u32), data)
+}
+
+#[target_feature(enable = "sse4.2", enable = "pclmulqdq")]
+#[cfg(target_arch = "x86_64")]
+unsafe fn crc32c_3way(crc: u64, block: &[u8], k_2stripe: u64, k_stripe: u64) -> u64 {
+    let stripe = block.len() / 3;
+    let (a, block) = block.split_at(stripe);
+    let (b, c) = block.split_at(stripe);
+
+    let mut crc0 = crc;
+    let mut crc1 = 0;
+    let mut crc2 = 0;
+
+    for ((a, b), c) in a
+        .chunks_exact(8)
+        .zip(b.chunks_exact(8))
+        .zip(c.chunks_exact(8))
+    {
+        crc0 = _mm_crc32_u64(crc0, u64::from_le_bytes(a.try_into().unwrap()));
+        crc1 = _mm_crc32_u64(crc1, u64::from_le_bytes(b.try_into().unwrap()));
+        crc2 = _mm_crc32_u64(crc2, u64::from_le_bytes(c.try_into().unwrap()));
+    }
+
+    // shift crc0 over 2 stripes and crc1 over 1 stripe
+    crc0 = _mm_crc32_u64(0, clmul_u64(crc0, k_2stripe));
+    crc1 = _mm_crc32_u64(0, clmul_u64(crc1, k_stripe));
+
+    crc0 ^ crc1 ^ crc2
+}
+
+#[target_feature(enable = "pclmulqdq")]
+#[cfg(target_arch = "x86_64")]
+unsafe fn clmul_u64(a: u64, b: u64) -> u64 {
+    let product = _mm_clmulepi64_si128(
+        _mm_cvtsi64_si128(a as i64),
+        _mm_cvtsi64_si128(b as i64),
+        0x00,
+    );
+    _mm_cvtsi128_si64(product) as u64
+}
+
 #[target_feature(enable = "sse4.2")]
 #[cfg(target_arch = "x86")]
 pub(crate) unsafe fn compute_crc32c_hardware_x86(prev_crc: u32, data: &[u8]) -> u32 {
@@ -88,6 +166,20 @@ pub(crate) unsafe fn compute_crc(
     x = fold_128(x, x1, k3k4);
     x = fold_128(x, x0, k3k4);
 
+    reduce_128(x, constants, rev_polynomial, data)
+}
+
+/// Folds the remaining 16 byte blocks of data into `x` and Barrett reduces the result to 32 bits
+#[target_feature(enable = "sse4.2", enable = "pclmulqdq", enable = "sse2")]
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe fn reduce_128(
+    mut x: __m128i,
+    constants: [u64; 7],
+    rev_polynomial: u64,
+    mut data: &[u8],
+) -> (u32, &[u8]) {
+    let k3k4 = _mm_set_epi64x(constants[3] as i64, constants[2] as i64);
+
     // fold 1*128 bits
     while data.len() >= 16 {
         x = fold_128(x, get_simd_128(&mut data), k3k4);
@@ -119,9 +211,208 @@ pub(crate) unsafe fn compute_crc(
     (!(_mm_extract_epi32(x, 1) as u32), data)
 }
 
+/// Same as [compute_crc] but folds 4*512 bits per iteration using VPCLMULQDQ on 512 bit registers.
+/// `wide_constants` are generated by `CustomCrc32::generate_simd_wide_reflected_constants`.
+#[target_feature(
+    enable = "avx512f",
+    enable = "vpclmulqdq",
+    enable = "sse4.2",
+    enable = "pclmulqdq"
+)]
+#[cfg(target_arch = "x86_64")]
+pub(crate) unsafe fn compute_crc_avx512(
+    prev_crc: u32,
+    constants: [u64; 7],
+    wide_constants: [u64; 6],
+    rev_polynomial: u64,
+    mut data: &[u8],
+) -> (u32, &[u8]) {
+    if data.len() < 256 {
+        return (prev_crc, data);
+    }
+
+    // this is safe since we already validated we have at least 256 bytes
+    let mut z3 = get_simd_512(&mut data);
+    let mut z2 = get_simd_512(&mut data);
+    let mut z1 = get_simd_512(&mut data);
+    let mut z0 = get_simd_512(&mut data);
+
+    z3 = _mm512_xor_si512(
+        z3,
+        _mm512_zextsi128_si512(_mm_cvtsi32_si128(!prev_crc as i32)),
+    );
+
+    let k = _mm512_broadcast_i32x4(_mm_set_epi64x(
+        wide_constants[1] as i64,
+        wide_constants[0] as i64,
+    ));
+    // fold 4*512 bits
+    while data.len() >= 256 {
+        z3 = fold_512(z3, get_simd_512(&mut data), k);
+        z2 = fold_512(z2, get_simd_512(&mut data), k);
+        z1 = fold_512(z1, get_simd_512(&mut data), k);
+        z0 = fold_512(z0, get_simd_512(&mut data), k);
+    }
+
+    //fold into 512 bits
+    let k1k2 = _mm512_broadcast_i32x4(_mm_set_epi64x(constants[1] as i64, constants[0] as i64));
+    let mut z = fold_512(z3, z2, k1k2);
+    z = fold_512(z, z1, k1k2);
+    z = fold_512(z, z0, k1k2);
+
+    // fold 1*512 bits
+    while data.len() >= 64 {
+        z = fold_512(z, get_simd_512(&mut data), k1k2);
+    }
+
+    //fold into 128 bits
+    let k3k4 = _mm_set_epi64x(constants[3] as i64, constants[2] as i64);
+    let mut x = fold_128(
+        _mm512_extracti32x4_epi32::<0>(z),
+        _mm512_extracti32x4_epi32::<1>(z),
+        k3k4,
+    );
+    x = fold_128(x, _mm512_extracti32x4_epi32::<2>(z), k3k4);
+    x = fold_128(x, _mm512_extracti32x4_epi32::<3>(z), k3k4);
+
+    reduce_128(x, constants, rev_polynomial, data)
+}
+
+/// Same as [compute_crc] but folds 4*256 bits per iteration using VPCLMULQDQ on 256 bit registers.
+/// `wide_constants` are generated by `CustomCrc32::generate_simd_wide_reflected_constants`.
+#[target_feature(
+    enable = "avx2",
+    enable = "vpclmulqdq",
+    enable = "sse4.2",
+    enable = "pclmulqdq"
+)]
+#[cfg(target_arch = "x86_64")]
+pub(crate) unsafe fn compute_crc_avx2(
+    prev_crc: u32,
+    constants: [u64; 7],
+    wide_constants: [u64; 6],
+    rev_polynomial: u64,
+    mut data: &[u8],
+) -> (u32, &[u8]) {
+    if data.len() < 128 {
+        return (prev_crc, data);
+    }
+
+    // this is safe since we already validated we have at least 128 bytes
+    let mut y3 = get_simd_256(&mut data);
+    let mut y2 = get_simd_256(&mut data);
+    let mut y1 = get_simd_256(&mut data);
+    let mut y0 = get_simd_256(&mut data);
+
+    y3 = _mm256_xor_si256(
+        y3,
+        _mm256_zextsi128_si256(_mm_cvtsi32_si128(!prev_crc as i32)),
+    );
+
+    let k = _mm256_broadcastsi128_si256(_mm_set_epi64x(
+        wide_constants[3] as i64,
+        wide_constants[2] as i64,
+    ));
+    // fold 4*256 bits
+    while data.len() >= 128 {
+        y3 = fold_256(y3, get_simd_256(&mut data), k);
+        y2 = fold_256(y2, get_simd_256(&mut data), k);
+        y1 = fold_256(y1, get_simd_256(&mut data), k);
+        y0 = fold_256(y0, get_simd_256(&mut data), k);
+    }
+
+    //fold into 256 bits
+    let k = _mm256_broadcastsi128_si256(_mm_set_epi64x(
+        wide_constants[5] as i64,
+        wide_constants[4] as i64,
+    ));
+    let mut y = fold_256(y3, y2, k);
+    y = fold_256(y, y1, k);
+    y = fold_256(y, y0, k);
+
+    // fold 1*256 bits
+    while data.len() >= 32 {
+        y = fold_256(y, get_simd_256(&mut data), k);
+    }
+
+    //fold into 128 bits
+    let k3k4 = _mm_set_epi64x(constants[3] as i64, constants[2] as i64);
+    let x = fold_128(
+        _mm256_extracti128_si256::<0>(y),
+        _mm256_extracti128_si256::<1>(y),
+        k3k4,
+    );
+
+    reduce_128(x, constants, rev_polynomial, data)
+}
+
+/// This function computes the crc values of non reflected (msb first) polynomials based on the
+/// non reflected algorithm in [intel's paper](https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/fast-crc-computation-generic-polynomials-pclmulqdq-paper.pdf).
+/// The constants are expected in the order generated by `CustomCrc32::generate_simd_constants`
+#[target_feature(enable = "sse4.2", enable = "pclmulqdq", enable = "sse2")]
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) unsafe fn compute_crc_msb(
+    prev_crc: u32,
+    constants: [u64; 7],
+    polynomial: u64,
+    mut data: &[u8],
+) -> (u32, &[u8]) {
+    if data.len() < 128 {
+        return (prev_crc, data);
+    }
+
+    // this is safe since we already validated we have at least 128 bytes
+    let mut x3 = get_simd_128_msb(&mut data);
+    let mut x2 = get_simd_128_msb(&mut data);
+    let mut x1 = get_simd_128_msb(&mut data);
+    let mut x0 = get_simd_128_msb(&mut data);
+
+    x3 = _mm_xor_si128(x3, _mm_set_epi32(!prev_crc as i32, 0, 0, 0));
+
+    let k1k2 = _mm_set_epi64x(constants[0] as i64, constants[1] as i64);
+    // fold 4*128 bits
+    while data.len() >= 64 {
+        x3 = fold_128(x3, get_simd_128_msb(&mut data), k1k2);
+        x2 = fold_128(x2, get_simd_128_msb(&mut data), k1k2);
+        x1 = fold_128(x1, get_simd_128_msb(&mut data), k1k2);
+        x0 = fold_128(x0, get_simd_128_msb(&mut data), k1k2);
+    }
+
+    //fold into 128 bits
+    let k3k4 = _mm_set_epi64x(constants[2] as i64, constants[3] as i64);
+    let mut x = fold_128(x3, x2, k3k4);
+    x = fold_128(x, x1, k3k4);
+    x = fold_128(x, x0, k3k4);
+
+    // fold 1*128 bits
+    while data.len() >= 16 {
+        x = fold_128(x, get_simd_128_msb(&mut data), k3k4);
+    }
+
+    // fold 128 bits to 96 bits
+    let k5k6 = _mm_set_epi64x(constants[4] as i64, constants[5] as i64);
+    let x2 = _mm_slli_si128(_mm_move_epi64(x), 4);
+    x = _mm_clmulepi64_si128(x, k5k6, 0x11);
+    x = _mm_xor_si128(x, x2);
+
+    // fold 96 bits to 64 bits
+    let x2 = _mm_move_epi64(x);
+    x = _mm_clmulepi64_si128(_mm_srli_si128(x, 8), k5k6, 0x00);
+    x = _mm_xor_si128(x, x2);
+
+    // Barret reduce to 32-bits
+    let pu = _mm_set_epi64x(polynomial as i64, constants[6] as i64);
+
+    let mut x2 = _mm_clmulepi64_si128(_mm_srli_epi64(x, 32), pu, 0x00);
+    x2 = _mm_clmulepi64_si128(_mm_srli_epi64(x2, 32), pu, 0x10);
+    x = _mm_xor_si128(x, x2);
+
+    (!(_mm_cvtsi128_si32(x) as u32), data)
+}
+
 #[target_feature(enable = "pclmulqdq,sse2")]
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-unsafe fn fold_128(a: __m128i, mut b: __m128i, constant: __m128i) -> __m128i {
+pub(crate) unsafe fn fold_128(a: __m128i, mut b: __m128i, constant: __m128i) -> __m128i {
     //let mut xmm0 = b;
     let xmm1 = _mm_clmulepi64_si128(a, constant, 0x00);
     let xmm2 = _mm_clmulepi64_si128(a, constant, 0x11);
@@ -130,11 +421,53 @@ unsafe fn fold_128(a: __m128i, mut b: __m128i, constant: __m128i) -> __m128i {
     b
 }
 
+#[target_feature(enable = "avx512f", enable = "vpclmulqdq")]
+#[cfg(target_arch = "x86_64")]
+unsafe fn fold_512(a: __m512i, b: __m512i, constant: __m512i) -> __m512i {
+    let lo = _mm512_clmulepi64_epi128(a, constant, 0x00);
+    let hi = _mm512_clmulepi64_epi128(a, constant, 0x11);
+    // three way xor of b, lo and hi
+    _mm512_ternarylogic_epi64::<0x96>(b, lo, hi)
+}
+
+#[target_feature(enable = "avx2", enable = "vpclmulqdq")]
+#[cfg(target_arch = "x86_64")]
+unsafe fn fold_256(a: __m256i, b: __m256i, constant: __m256i) -> __m256i {
+    let lo = _mm256_clmulepi64_epi128(a, constant, 0x00);
+    let hi = _mm256_clmulepi64_epi128(a, constant, 0x11);
+    _mm256_xor_si256(b, _mm256_xor_si256(lo, hi))
+}
+
+#[target_feature(enable = "avx512f")]
+#[cfg(target_arch = "x86_64")]
+unsafe fn get_simd_512(data: &mut &[u8]) -> __m512i {
+    let x = _mm512_loadu_si512(data[..64].as_ptr() as *const __m512i);
+    *data = &data[64..];
+    x
+}
+
+#[target_feature(enable = "avx2")]
+#[cfg(target_arch = "x86_64")]
+unsafe fn get_simd_256(data: &mut &[u8]) -> __m256i {
+    let x = _mm256_loadu_si256(data[..32].as_ptr() as *const __m256i);
+    *data = &data[32..];
+    x
+}
+
 #[target_feature(enable = "sse2")]
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-unsafe fn get_simd_128(data: &mut &[u8]) -> __m128i {
+pub(crate) unsafe fn get_simd_128(data: &mut &[u8]) -> __m128i {
     let x1 = i64::from_le_bytes(data[0..8].try_into().unwrap());
     let x2 = i64::from_le_bytes(data[8..16].try_into().unwrap());
     *data = &data[16..];
     _mm_set_epi64x(x2, x1)
 }
+
+#[target_feature(enable = "sse2")]
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+unsafe fn get_simd_128_msb(data: &mut &[u8]) -> __m128i {
+    let x1 = i64::from_be_bytes(data[0..8].try_into().unwrap());
+    let x2 = i64::from_be_bytes(data[8..16].try_into().unwrap());
+    *data = &data[16..];
+    _mm_set_epi64x(x1, x2)
+}
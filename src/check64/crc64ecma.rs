@@ -1,46 +1,196 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::backend::{Backend, UnsupportedBackend};
+use crate::catalogue::CRC_64_XZ;
 use crate::check64::custom_crc64::CustomCrc64;
-use crate::check64::{Crc64Digest, UpdateFn};
+use crate::check64::{Crc64Combiner, UpdateFn};
+use crate::crc::{self, CrcDigest};
+use crate::params::CrcParams;
 
-const CRC64_POLYNOMIAL: u64 = 0x42F0E1EBA9EA3693;
-const CRC64_LOOKUP_TABLE: [[u64; 256]; 16] =
-    CustomCrc64::generate_lookup_table_16(CRC64_POLYNOMIAL);
+#[cfg(feature = "parallel")]
+use crate::parallel;
 
+#[cfg(all(feature = "hardware", any(target_arch = "x86", target_arch = "x86_64")))]
+use crate::check64::platform::x86::compute_crc64;
+
+#[cfg(all(feature = "hardware", target_arch = "aarch64"))]
+use crate::check64::platform::arm::compute_crc64;
+
+const CRC64_PARAMS: CrcParams = CRC_64_XZ;
+const CRC64_POLYNOMIAL: u64 = CRC64_PARAMS.poly;
+static CRC64_LOOKUP_TABLE: [[u64; 256]; 16] =
+    CustomCrc64::generate_lookup_table_16(CRC64_POLYNOMIAL);
+// statics can not be read in const fns, the const checksum uses a single table const instead
+const CRC64_CONST_LOOKUP_TABLE: [u64; 256] = CustomCrc64::generate_lookup_table(CRC64_POLYNOMIAL);
 
 #[cfg(feature = "hardware")]
-const CRC64_POLYNOMIAL_REV: u64 = 0xC96C5795D7870F42;
+const CRC64_SIMD_CONSTANTS: [u64; 6] =
+    CustomCrc64::generate_simd_reflected_constants(CRC64_POLYNOMIAL);
 
+#[derive(Clone)]
 pub struct Crc64ECMA {
     state: u64,
     compute: UpdateFn,
+    backend: Backend,
 }
 
 impl Crc64ECMA {
-    /// Creates a new `Crc64` using naive approach
+    /// Creates a new `Crc64ECMA` using naive approach
     pub fn new_naive() -> Self {
         Self {
             state: 0,
             compute: Self::compute_naive,
+            backend: Backend::Naive,
         }
     }
 
-    /// Creates a new `Crc64` using a table lookup approach
+    /// Creates a new `Crc64ECMA` using a table lookup approach
     pub fn new_lookup() -> Self {
         Self {
             state: 0,
             compute: Self::compute_lookup,
+            backend: Backend::Lookup,
+        }
+    }
+
+    #[cfg(feature = "hardware")]
+    /// Creates a new `Crc64ECMA` using simd intrinsics based on
+    /// [intel's paper](https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/fast-crc-computation-generic-polynomials-pclmulqdq-paper.pdf)
+    /// - x86 and x86_64 requires the cpu features sse4.1, pclmulqdq
+    /// - aarch64 requires the cpu features neon, aes
+    /// - Otherwise defaults to table lookup approach
+    pub fn new_simd() -> Self {
+        Backend::SIMD
+            .into_iter()
+            .find_map(|backend| Self::new_with_backend(backend).ok())
+            .unwrap_or_else(Self::new_lookup)
+    }
+
+    /// Creates a new `Crc64ECMA` using the given backend. Unlike the other constructors this does
+    /// not fall back to a slower backend, and fails if the backend is not supported.
+    pub fn new_with_backend(backend: Backend) -> Result<Self, UnsupportedBackend> {
+        let compute = Self::select(backend).ok_or(UnsupportedBackend(backend))?;
+        Ok(Self {
+            state: 0,
+            compute,
+            backend,
+        })
+    }
+
+    /// Returns the backends `Crc64ECMA` supports on this cpu with the enabled crate features
+    pub fn supported_backends() -> impl Iterator<Item = Backend> {
+        Backend::ALL
+            .into_iter()
+            .filter(|&backend| Self::select(backend).is_some())
+    }
+
+    /// Returns the backend this instance computes crcs with, after falling back from
+    /// unsupported cpu features
+    pub fn selected_backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Resets the crc to its initial state, keeping the selected backend
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Updates digest with data split across up to `threads` threads, each using the approach
+    /// this instance was created with. The partial crcs are merged using [combine](Self::combine).
+    pub fn update_parallel(&mut self, data: &[u8], threads: usize) {
+        self.state =
+            parallel::update_parallel(self.state, data, threads, 0, self.compute, Self::combine);
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Computes the `Crc64ECMA` of data using up to `threads` threads, see [update_parallel](Self::update_parallel).
+    /// - Uses the simd approach when the `hardware` feature is enabled
+    /// - Otherwise uses table lookup approach
+    pub fn checksum_parallel(data: &[u8], threads: usize) -> u64 {
+        #[cfg(feature = "hardware")]
+        let mut crc = Self::new_simd();
+        #[cfg(not(feature = "hardware"))]
+        let mut crc = Self::new_lookup();
+        crc.update_parallel(data, threads);
+        crc.digest()
+    }
+
+    /// Computes the crc of `A || B` given `crc_a`, `crc_b` and the length of `B` in bytes
+    pub fn combine(crc_a: u64, crc_b: u64, len_b: u64) -> u64 {
+        Self::combiner(len_b).combine(crc_a, crc_b)
+    }
+
+    /// Creates a [Crc64Combiner] for combining crcs where the second chunk is `len_b` bytes long
+    pub fn combiner(len_b: u64) -> Crc64Combiner {
+        Crc64Combiner::new(CRC64_PARAMS, len_b)
+    }
+
+    /// Computes the crc of data after replacing `old` at `offset` with `new`, given `old_crc`, the
+    /// crc of the original `total_len` bytes, see [Crc::patch](crate::crc::Crc::patch).
+    pub fn patch(&self, old_crc: u64, total_len: u64, offset: u64, old: &[u8], new: &[u8]) -> u64 {
+        crc::patch(
+            CRC64_PARAMS,
+            self.compute,
+            old_crc,
+            total_len,
+            offset,
+            old,
+            new,
+        )
+    }
+
+    /// Returns the 8 bytes which, written over `data[offset..offset + 8]`, make the crc of
+    /// `data` equal to `target`, e.g. to keep the expected checksum of a patched firmware image.
+    ///
+    /// # Panics
+    /// Panics if `offset + 8` exceeds the length of `data`.
+    pub fn forge(&self, data: &[u8], offset: usize, target: u64) -> [u8; 8] {
+        let bytes = crc::forge(CRC64_PARAMS, self.compute, 0, data, offset, target)
+            .expect("the polynomial has the x^0 term");
+        bytes.to_le_bytes()
+    }
+
+    /// Computes the crc of `data` using the table lookup approach, usable in const contexts
+    pub const fn const_checksum(data: &[u8]) -> u64 {
+        CustomCrc64::const_lookup(0, &CRC64_CONST_LOOKUP_TABLE, data)
+    }
+
+    fn select(backend: Backend) -> Option<UpdateFn> {
+        match backend {
+            Backend::Naive => Some(Self::compute_naive),
+            Backend::Lookup => Some(Self::compute_lookup),
+            #[cfg(all(
+                feature = "hardware",
+                any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+            ))]
+            _ if CustomCrc64::is_simd_supported(backend) => Some(Self::compute_simd),
+            _ => None,
         }
     }
 
     fn compute_naive(prev_crc: u64, data: &[u8]) -> u64 {
-        CustomCrc64::crc64_naive(prev_crc, CRC64_POLYNOMIAL, data)
+        CustomCrc64::naive(prev_crc, CRC64_POLYNOMIAL, data)
     }
 
     fn compute_lookup(prev_crc: u64, data: &[u8]) -> u64 {
-        CustomCrc64::crc64_lookup(prev_crc, &CRC64_LOOKUP_TABLE, data)
+        CustomCrc64::lookup(prev_crc, &CRC64_LOOKUP_TABLE, data)
+    }
+
+    #[cfg(all(
+        feature = "hardware",
+        any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    fn compute_simd(mut prev_crc: u64, mut data: &[u8]) -> u64 {
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe { compute_crc64(prev_crc, CRC64_SIMD_CONSTANTS, data) };
+        Self::compute_lookup(prev_crc, data)
     }
 }
 
-impl Crc64Digest for Crc64ECMA {
+impl CrcDigest for Crc64ECMA {
+    type Output = u64;
+
     fn update(&mut self, data: &[u8]) {
         self.state = (self.compute)(self.state, data);
     }
@@ -48,13 +198,68 @@ impl Crc64Digest for Crc64ECMA {
     fn digest(&self) -> u64 {
         self.state
     }
+
+    fn update_zeros(&mut self, len: u64) {
+        self.state = Self::combiner(len).append_zeros(self.state);
+    }
+}
+
+impl Hasher for Crc64ECMA {
+    fn finish(&self) -> u64 {
+        self.digest()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+}
+
+impl Default for Crc64ECMA {
+    /// Creates a new `Crc64ECMA` using the simd approach when the `hardware` feature is enabled and the
+    /// table lookup approach otherwise
+    fn default() -> Self {
+        #[cfg(feature = "hardware")]
+        return Self::new_simd();
+        #[cfg(not(feature = "hardware"))]
+        return Self::new_lookup();
+    }
+}
+
+/// Builds [Crc64ECMA] hashers for use in hash maps. The backend is selected once when the builder is
+/// created, using the simd approach when the `hardware` feature is enabled and the table lookup
+/// approach otherwise.
+#[derive(Clone)]
+pub struct Crc64EcmaBuildHasher {
+    hasher: Crc64ECMA,
+}
+
+impl Crc64EcmaBuildHasher {
+    /// Creates a new `Crc64EcmaBuildHasher`
+    pub fn new() -> Self {
+        Self {
+            hasher: Crc64ECMA::default(),
+        }
+    }
+}
+
+impl Default for Crc64EcmaBuildHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for Crc64EcmaBuildHasher {
+    type Hasher = Crc64ECMA;
+
+    fn build_hasher(&self) -> Crc64ECMA {
+        self.hasher.clone()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    use super::*;
+    use crate::test_util::{assert_forge, assert_patch, assert_update_zeros};
 
     static EMPTY_DATA: &[u8; 0] = b"";
     static SMALL_DATA_1: &[u8; 9] = b"123456789";
@@ -97,4 +302,120 @@ mod tests {
         test_lookup(LARGE_DATA_1, LARGE_DATA_1_CRC64);
         test_lookup(LARGE_DATA_2, LARGE_DATA_2_CRC64);
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "hardware")]
+    fn test_simd(data: &[u8], expected_crc: u64) {
+        let mut crc = Crc64ECMA::new_simd();
+        crc.update(data);
+        assert_eq!(crc.digest(), expected_crc);
+    }
+
+    #[test]
+    #[cfg(feature = "hardware")]
+    fn test_crc64ecma_simd() {
+        test_simd(EMPTY_DATA, EMPTY_DATA_CRC64);
+        test_simd(SMALL_DATA_1, SMALL_DATA_1_CRC64);
+        test_simd(SMALL_DATA_2, SMALL_DATA_2_CRC64);
+        test_simd(LARGE_DATA_1, LARGE_DATA_1_CRC64);
+        test_simd(LARGE_DATA_2, LARGE_DATA_2_CRC64);
+    }
+
+    #[test]
+    fn test_crc64ecma_combine() {
+        for split in [0, 1, 9, 100, LARGE_DATA_2.len()] {
+            let (a, b) = LARGE_DATA_2.split_at(split);
+            let mut crc_a = Crc64ECMA::new_lookup();
+            crc_a.update(a);
+            let mut crc_b = Crc64ECMA::new_lookup();
+            crc_b.update(b);
+            let len_b = b.len() as u64;
+            assert_eq!(
+                Crc64ECMA::combine(crc_a.digest(), crc_b.digest(), len_b),
+                LARGE_DATA_2_CRC64
+            );
+            assert_eq!(
+                Crc64ECMA::combiner(len_b).combine(crc_a.digest(), crc_b.digest()),
+                LARGE_DATA_2_CRC64
+            );
+        }
+    }
+
+    #[test]
+    fn test_crc64ecma_backend() {
+        assert_eq!(Crc64ECMA::new_naive().selected_backend(), Backend::Naive);
+        assert_eq!(Crc64ECMA::new_lookup().selected_backend(), Backend::Lookup);
+        #[cfg(feature = "hardware")]
+        assert_eq!(
+            Crc64ECMA::new_simd().selected_backend(),
+            Crc64ECMA::supported_backends().last().unwrap()
+        );
+
+        for backend in Backend::ALL {
+            let supported = Crc64ECMA::supported_backends().any(|b| b == backend);
+            match Crc64ECMA::new_with_backend(backend) {
+                Ok(mut crc) => {
+                    assert!(supported);
+                    crc.update(LARGE_DATA_2);
+                    assert_eq!(crc.digest(), LARGE_DATA_2_CRC64);
+                }
+                Err(err) => {
+                    assert!(!supported);
+                    assert_eq!(err, UnsupportedBackend(backend));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_crc64ecma_hasher() {
+        let build_hasher = Crc64EcmaBuildHasher::new();
+        let mut hasher = build_hasher.build_hasher();
+        hasher.write(SMALL_DATA_1);
+        assert_eq!(hasher.finish(), SMALL_DATA_1_CRC64);
+        assert_eq!(
+            build_hasher.hash_one(SMALL_DATA_1),
+            build_hasher.hash_one(SMALL_DATA_1)
+        );
+
+        let mut map = std::collections::HashMap::with_hasher(build_hasher);
+        map.insert(&SMALL_DATA_1[..], 1);
+        map.insert(&SMALL_DATA_2[..], 2);
+        assert_eq!(map.get(&SMALL_DATA_1[..]), Some(&1));
+        assert_eq!(map.get(&SMALL_DATA_2[..]), Some(&2));
+    }
+
+    #[test]
+    fn test_crc64ecma_const_checksum() {
+        const SMALL_DATA_1_CONST_CRC64: u64 = Crc64ECMA::const_checksum(SMALL_DATA_1);
+        assert_eq!(SMALL_DATA_1_CONST_CRC64, SMALL_DATA_1_CRC64);
+        assert_eq!(Crc64ECMA::const_checksum(EMPTY_DATA), EMPTY_DATA_CRC64);
+        assert_eq!(Crc64ECMA::const_checksum(SMALL_DATA_2), SMALL_DATA_2_CRC64);
+        assert_eq!(Crc64ECMA::const_checksum(LARGE_DATA_1), LARGE_DATA_1_CRC64);
+        assert_eq!(Crc64ECMA::const_checksum(LARGE_DATA_2), LARGE_DATA_2_CRC64);
+    }
+
+    #[test]
+    fn test_crc64ecma_update_zeros() {
+        for backend in Crc64ECMA::supported_backends() {
+            assert_update_zeros(Crc64ECMA::new_with_backend(backend).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_crc64ecma_patch() {
+        for backend in Crc64ECMA::supported_backends() {
+            let crc = Crc64ECMA::new_with_backend(backend).unwrap();
+            assert_patch(&crc, |old_crc, total_len, offset, old, new| {
+                crc.patch(old_crc, total_len, offset, old, new)
+            });
+        }
+    }
+
+    #[test]
+    fn test_crc64ecma_forge() {
+        for backend in Crc64ECMA::supported_backends() {
+            let crc = Crc64ECMA::new_with_backend(backend).unwrap();
+            assert_forge(&crc, |data, offset, target| crc.forge(data, offset, target));
+        }
+    }
+}
@@ -1,203 +1,215 @@
-pub struct CustomCrc64 {}
+#[cfg(feature = "hardware")]
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::check64::platform::x86::compute_crc64;
+
+#[cfg(feature = "hardware")]
+#[cfg(target_arch = "aarch64")]
+use crate::check64::platform::arm::compute_crc64;
+
+use crate::backend::Backend;
+use crate::crc::sealed::SimdKernels;
+use crate::crc::{ComputeFn, Crc};
+
+/// Crc64 for any polynomial and crc parameters, see [Crc] for the constructors
+pub type CustomCrc64 = Crc<u64>;
+
+impl SimdKernels for u64 {
+    #[cfg(feature = "hardware")]
+    type Constants = [u64; 6];
+    #[cfg(not(feature = "hardware"))]
+    type Constants = ();
+
+    #[cfg(feature = "hardware")]
+    fn constants(polynomial: u64, _refin: bool) -> [u64; 6] {
+        CustomCrc64::generate_simd_reflected_constants(polynomial)
+    }
 
-impl CustomCrc64 {
-    pub(crate) fn crc64_lookup(
-        prev_crc: u64,
-        lookup_table: &[[u64; 256]; 16],
-        mut data: &[u8],
-    ) -> u64 {
-        let mut crc: u64 = !prev_crc;
-
-        while data.len() >= 16 {
-            crc = lookup_table[0][data[15] as usize]
-                ^ lookup_table[1][data[14] as usize]
-                ^ lookup_table[2][data[13] as usize]
-                ^ lookup_table[3][data[12] as usize]
-                ^ lookup_table[4][data[11] as usize]
-                ^ lookup_table[5][data[10] as usize]
-                ^ lookup_table[6][data[9] as usize]
-                ^ lookup_table[7][data[8] as usize]
-                ^ lookup_table[8][data[7] as usize ^ ((crc >> 56) & 0xFF) as usize]
-                ^ lookup_table[9][data[6] as usize ^ ((crc >> 48) & 0xFF) as usize]
-                ^ lookup_table[10][data[5] as usize ^ ((crc >> 40) & 0xFF) as usize]
-                ^ lookup_table[11][data[4] as usize ^ ((crc >> 32) & 0xFF) as usize]
-                ^ lookup_table[12][data[3] as usize ^ ((crc >> 24) & 0xFF) as usize]
-                ^ lookup_table[13][data[2] as usize ^ ((crc >> 16) & 0xFF) as usize]
-                ^ lookup_table[14][data[1] as usize ^ ((crc >> 8) & 0xFF) as usize]
-                ^ lookup_table[15][data[0] as usize ^ ((crc) & 0xFF) as usize];
-            data = &data[16..];
+    #[cfg(not(feature = "hardware"))]
+    fn constants(_polynomial: u64, _refin: bool) {}
+
+    /// Only the 128 bit kernel for reflected polynomials is implemented for crc64
+    fn select(refin: bool, backend: Backend) -> Option<ComputeFn<u64>> {
+        match (backend, refin) {
+            #[cfg(all(
+                feature = "hardware",
+                any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")
+            ))]
+            (_, true) if CustomCrc64::is_simd_supported(backend) => Some(CustomCrc64::compute_simd),
+            _ => None,
         }
-        for &b in data {
-            crc = lookup_table[0][((crc as u8) ^ b) as usize] ^ (crc >> 8);
-        }
-
-        !crc
     }
+}
 
-    pub(super) const fn generate_lookup_table_16(polynomial: u64) -> [[u64; 256]; 16] {
-        let mut table = [[0; 256]; 16];
-
-        table[0] = Self::generate_lookup_table(polynomial);
-        let mut length = 0;
-        let mut j = 1;
-
-        while length < 256 {
-            let mut crc = table[0][length];
-            while j < 16 {
-                crc = (crc >> 8) ^ table[0][crc as u8 as usize];
-                table[j][length] = crc;
-                j += 1;
-            }
-            j = 1;
-            length += 1;
-        }
+impl CustomCrc64 {
+    /// Returns the 8 bytes which, written over `data[offset..offset + 8]`, make the crc of `data`
+    /// equal to `target`, or `None` if no such bytes exist, which only happens for polynomials
+    /// without the x^0 term.
+    ///
+    /// # Panics
+    /// Panics if `offset + 8` exceeds the length of `data`.
+    pub fn forge(&self, data: &[u8], offset: usize, target: u64) -> Option<[u8; 8]> {
+        let bytes = self.forge_bytes(data, offset, target)?;
+        Some(bytes.to_le_bytes())
+    }
+}
 
-        table
+#[cfg(feature = "hardware")]
+impl CustomCrc64 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+    fn compute_simd(&self, mut prev_crc: u64, mut data: &[u8]) -> u64 {
+        // safety: only selected when is_simd_supported found the required cpu features
+        (prev_crc, data) = unsafe { compute_crc64(prev_crc, self.simd_constants, data) };
+        Self::lookup(prev_crc, &self.lookup_table, data)
     }
 
-    pub(super) const fn generate_lookup_table(mut polynomial: u64) -> [u64; 256] {
-        let mut table = [0; 256];
-        polynomial = polynomial.reverse_bits();
-        let mut length = 0;
-        let mut crc;
-        let mut j = 0;
-
-        while length < 256 {
-            crc = length;
-            while j < 8 {
-                if crc & 1u64 == 1 {
-                    crc = (crc >> 1) ^ polynomial;
-                } else {
-                    crc >>= 1;
-                }
-                j += 1;
+    /// Returns whether the cpu supports the features required by a simd backend. Only the 128
+    /// bit backends are implemented for crc64.
+    pub(crate) fn is_simd_supported(backend: Backend) -> bool {
+        match backend {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Backend::SimdSse => {
+                x86_feature_detected!("sse4.1", "pclmulqdq")
             }
-            table[length as usize] = crc;
-            j = 0;
-            length += 1;
+            #[cfg(target_arch = "aarch64")]
+            Backend::SimdNeon => {
+                aarch64_feature_detected!("neon", "aes")
+            }
+            _ => false,
         }
-
-        table
     }
 
-    pub(crate) fn crc64_naive(prev_crc: u64, polynomial: u64, data: &[u8]) -> u64 {
-        let mut crc = !prev_crc;
-        let polynomial = polynomial.reverse_bits();
+    /// Generates the reflected `[k1', k2', k3', k4', u', p']`, which are
+    /// `[x575, x511, x191, x127, u, polynomial]` where `xn = x^n mod polynomial` and `u` is the
+    /// barrett reduction constant `x^128 / polynomial`. The 65 bit `u` and `polynomial` are
+    /// reflected without their implicit x^64 term.
+    pub(crate) const fn generate_simd_reflected_constants(polynomial: u64) -> [u64; 6] {
+        // the extra -1 in the folding constants accounts for the carry less multiplication
+        // of two 64 bit reflected values resulting in a 127 bit value
+        let distances = [575, 511, 191, 127];
+        let mut constants = [0; 6];
         let mut i = 0;
-        let mut j = 0;
-
-        while i < data.len() {
-            crc ^= data[i] as u64;
-
-            while j < 8 {
-                if crc & 1u64 == 1 {
-                    crc = crc >> 1 ^ polynomial;
-                } else {
-                    crc >>= 1;
-                }
-                j += 1;
-            }
-            j = 0;
+        while i < distances.len() {
+            constants[i] = Self::xn_mod(distances[i], polynomial).reverse_bits();
             i += 1;
         }
-
-        !crc
+        constants[4] = Self::barrett_quotient(polynomial).reverse_bits() << 1 | 1;
+        constants[5] = polynomial.reverse_bits() << 1 | 1;
+        constants
     }
+}
 
-    pub(super) const fn generate_simd_reflected_constants(mut polynomial: u64) -> [u64; 7] {
-        let polynomial = (polynomial as u128) & 1 << 64;
-        // x32
-        let x32 = Self::division(1 << 32, polynomial).1;
-
-        //x64
-        let x64 = Self::division(Self::carry_less_mul(x32, x32), polynomial).1;
-
-        //x(128) and x(192)
-        let x128 = Self::division(Self::carry_less_mul(x64, x64), polynomial).1;
-        let x192 = Self::division(Self::carry_less_mul(x64, x32), polynomial).1;
-        let x96 = Self::division(Self::carry_less_mul(x64, x32), polynomial).1;
-        let x160 = Self::division(Self::carry_less_mul(x96, x64), polynomial).1;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::UnsupportedBackend;
+    use crate::catalogue::CRC_64_NVME;
+    use crate::crc::CrcDigest;
 
-        //x(4*128 - 32) and x(4*128 + 32)
-        let x224 = Self::division(Self::carry_less_mul(x160, x64), polynomial).1;
-        let x256 = Self::division(Self::carry_less_mul(x224, x32), polynomial).1;
-        let x480 = Self::division(Self::carry_less_mul(x256, x224), polynomial).1;
-        let x544 = Self::division(Self::carry_less_mul(x480, x64), polynomial).1;
+    const POLYNOMIAL: u64 = 0x42F0E1EBA9EA3693;
+    const NVME_POLYNOMIAL: u64 = 0xAD93D23594C93659;
 
-        let u = 0x1_0000_0000_0000_0000 | Self::division(polynomial << 64, polynomial).0;
+    static LARGE_DATA_2: &[u8; 241] = include_bytes!("../../sample_files/test_data_odd_size.txt");
 
-        // [k1', k2', k3', k4', k5', k6', u']
-        let mut constants = [0; 7];
-        constants[0] = Self::reverse_constant(x544);
-        constants[1] = Self::reverse_constant(x480);
-        constants[2] = Self::reverse_constant(x160);
-        constants[3] = Self::reverse_constant(x96);
-        constants[4] = Self::reverse_constant(x192);
-        constants[5] = Self::reverse_constant(x128);
+    const LARGE_DATA_2_CRC64: u64 = 0xce8eb22c0606e740;
 
-        constants[6] = Self::reverse_constant(u);
+    #[test]
+    #[cfg(feature = "hardware")]
+    fn test_simd_reflected_constants() {
+        let constants = CustomCrc64::generate_simd_reflected_constants(POLYNOMIAL);
 
-        constants
+        assert_eq!(constants[0], 0x6ae3_efbb_9dd4_41f3);
+        assert_eq!(constants[1], 0x081f_6054_a784_2df4);
+        assert_eq!(constants[2], 0xe05d_d497_ca39_3ae4);
+        assert_eq!(constants[3], 0xdabe_95af_c787_5f40);
+        assert_eq!(constants[4], 0x9c3e_466c_1729_63d5);
+        assert_eq!(constants[5], 0x92d8_af2b_af0e_1e85);
     }
 
-    const fn reverse_constant(mut constant: u128) -> u64 {
-        let mut reversed_constant = 0;
-
-        let mut count = 0;
-
-        while count < 65 {
-            reversed_constant = (reversed_constant << 1) ^ (constant & 1);
-            constant >>= 1;
-            count += 1;
+    #[test]
+    fn test_custom_crc64() {
+        let mut crc = CustomCrc64::new_naive(POLYNOMIAL);
+        crc.update(LARGE_DATA_2);
+        assert_eq!(crc.digest(), LARGE_DATA_2_CRC64);
+        crc = CustomCrc64::new_lookup(POLYNOMIAL);
+        crc.update(LARGE_DATA_2);
+        assert_eq!(crc.digest(), LARGE_DATA_2_CRC64);
+        #[cfg(feature = "hardware")]
+        {
+            crc = CustomCrc64::new_simd(POLYNOMIAL);
+            crc.update(LARGE_DATA_2);
+            assert_eq!(crc.digest(), LARGE_DATA_2_CRC64);
         }
-        reversed_constant as u64
     }
 
-    pub const fn division(dividend: u128, polynomial: u128) -> (u128, u128) {
-        let mut remainder = dividend;
-        let mut quotient = 0;
-        let mut count = 0;
-        while count < 64 {
-            let msb = remainder >> 127;
-            quotient = (quotient << 1) ^ msb;
-            remainder = (remainder << 1) ^ (msb * (polynomial << 64));
-            count += 1;
+    #[test]
+    fn test_custom_crc64_nvme() {
+        let mut crc = CustomCrc64::new_naive(NVME_POLYNOMIAL);
+        crc.update(b"123456789");
+        assert_eq!(crc.digest(), 0xAE8B14860A799888);
+        crc = CustomCrc64::new_lookup(NVME_POLYNOMIAL);
+        crc.update(b"123456789");
+        assert_eq!(crc.digest(), 0xAE8B14860A799888);
+        #[cfg(feature = "hardware")]
+        {
+            crc = CustomCrc64::new_simd(NVME_POLYNOMIAL);
+            crc.update(LARGE_DATA_2);
+            let mut expected = CustomCrc64::new_naive(NVME_POLYNOMIAL);
+            expected.update(LARGE_DATA_2);
+            assert_eq!(crc.digest(), expected.digest());
         }
-        (quotient, (remainder >> 64))
     }
 
-    pub const fn carry_less_mul(a: u128, b: u128) -> u128 {
-        let mut result = 0;
-        let mut count = 0;
-        while count < 64 {
-            if (b >> count) & 1 == 1 {
-                result ^= (a as u128) << count
-            }
-            count += 1;
+    #[test]
+    fn test_custom_crc64_new_with_backend() {
+        let params = CRC_64_NVME;
+        assert_eq!(
+            CustomCrc64::supported_backends(params).next(),
+            Some(Backend::Naive)
+        );
+        for backend in CustomCrc64::supported_backends(params) {
+            let mut crc = CustomCrc64::new_with_backend(params, backend).unwrap();
+            assert_eq!(crc.selected_backend(), backend);
+            crc.update(b"123456789");
+            assert_eq!(crc.digest(), params.check);
         }
-        result
+        assert_eq!(
+            CustomCrc64::new_with_backend(params, Backend::Hardware).err(),
+            Some(UnsupportedBackend(Backend::Hardware))
+        );
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::CustomCrc64;
-
-    const POLYNOMIAL: u64 = 0x42F0E1EBA9EA3693;
 
     #[test]
-    fn test_carry_less_mul() {
-        assert_eq!(
-            CustomCrc64::carry_less_mul(0x5a2d_8244_0f1e_3e50, 0xcae9_00d5_fed9_262f),
-            0x39ca_c5ca_fc66_6bf3_25bc_9dd4_c0f3_6330,
-        )
+    fn test_custom_crc64_combine() {
+        for polynomial in [POLYNOMIAL, NVME_POLYNOMIAL] {
+            let mut expected = CustomCrc64::new_lookup(polynomial);
+            expected.update(LARGE_DATA_2);
+            for split in [0, 1, 9, 100, LARGE_DATA_2.len()] {
+                let (a, b) = LARGE_DATA_2.split_at(split);
+                let mut crc_a = CustomCrc64::new_lookup(polynomial);
+                crc_a.update(a);
+                let mut crc_b = CustomCrc64::new_lookup(polynomial);
+                crc_b.update(b);
+                assert_eq!(
+                    crc_a.combine(crc_a.digest(), crc_b.digest(), b.len() as u64),
+                    expected.digest()
+                );
+            }
+        }
     }
-    
-    fn test_simd_constants() {
-        let constants = CustomCrc64::generate_simd_reflected_constants(POLYNOMIAL);
 
-        println!("{:0x}", constants[6]);
-        println!("{:0x}", 0xdabe_95af_c787_5f40u64);
-        assert_eq!(constants[6], 0xdabe_95af_c787_5f40)
+    #[test]
+    fn test_custom_crc64_forge() {
+        let mut data = *LARGE_DATA_2;
+        for polynomial in [POLYNOMIAL, NVME_POLYNOMIAL] {
+            for offset in [0, 9, 120, 233] {
+                let crc = CustomCrc64::new_lookup(polynomial);
+                let target = 0x0123_4567_89AB_CDEF ^ offset as u64;
+                let bytes = crc.forge(&data, offset, target).unwrap();
+                data[offset..offset + 8].copy_from_slice(&bytes);
+                let mut forged = crc.clone();
+                forged.update(&data);
+                assert_eq!(forged.digest(), target);
+            }
+        }
     }
-}
\ No newline at end of file
+}
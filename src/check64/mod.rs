@@ -1,15 +1,33 @@
-pub(crate) mod crc64ecma;
-mod custom_crc64;
+mod crc64ecma;
 mod crc64iso;
+mod custom_crc64;
+
+#[cfg(any(
+    all(feature = "hardware", any(target_arch = "x86", target_arch = "x86_64")),
+    all(feature = "hardware", target_arch = "aarch64")
+))]
+mod platform;
+
+use crate::crc::CrcCombiner;
 
 type UpdateFn = fn(u64, &[u8]) -> u64;
 
-pub trait Crc64Digest {
-    /// Update digest with data
-    fn update(&mut self, data: &[u8]);
+/// The digest trait of the crc64 types, kept under its previous name. It is
+/// [CrcDigest](crate::crc::CrcDigest), so importing either name brings `update` and `digest` into
+/// scope.
+///
+/// ```
+/// use librscrc::check64::{Crc64ECMA, Crc64Digest};
+///
+/// let mut crc = Crc64ECMA::new_lookup();
+/// crc.update(b"123456789");
+/// assert_eq!(crc.digest(), 0x995DC9BBDF1939FA);
+/// ```
+pub use crate::crc::CrcDigest as Crc64Digest;
 
-    /// Returns crc32 digest
-    fn digest(&self) -> u64;
-}
+/// Combines crc64s, see [CrcCombiner]
+pub type Crc64Combiner = CrcCombiner<u64>;
 
-pub use crc64ecma::Crc64ECMA;
\ No newline at end of file
+pub use crc64ecma::{Crc64ECMA, Crc64EcmaBuildHasher};
+pub use crc64iso::{Crc64ISO, Crc64IsoBuildHasher};
+pub use custom_crc64::CustomCrc64;
@@ -0,0 +1,64 @@
+use core::arch::aarch64::{
+    vdupq_n_u64, vdupq_n_u8, veorq_u64, vextq_u8, vgetq_lane_u64, vld1q_u64, vreinterpretq_u64_u8,
+    vreinterpretq_u8_u64, vsetq_lane_u64,
+};
+
+use crate::check32::platform::arm::{fold_128, get_simd_128, pmull_00, pmull_01};
+
+/// This function computes the crc64 values using the same folding approach as
+/// [compute_crc](crate::check32::platform::arm::compute_crc), followed by a 64 bit barrett reduction.
+/// The constants are expected in the order generated by `CustomCrc64::generate_simd_reflected_constants`
+#[target_feature(enable = "neon", enable = "aes")]
+pub(crate) unsafe fn compute_crc64(
+    prev_crc: u64,
+    constants: [u64; 6],
+    mut data: &[u8],
+) -> (u64, &[u8]) {
+    if data.len() < 128 {
+        return (prev_crc, data);
+    }
+
+    // this is safe since we already validated we have at least 128 bytes
+    let mut x3 = get_simd_128(&mut data);
+    let mut x2 = get_simd_128(&mut data);
+    let mut x1 = get_simd_128(&mut data);
+    let mut x0 = get_simd_128(&mut data);
+
+    x3 = veorq_u64(x3, vsetq_lane_u64(!prev_crc, vdupq_n_u64(0), 0));
+
+    let k1k2 = vld1q_u64([constants[0], constants[1]].as_ptr());
+
+    while data.len() >= 64 {
+        x3 = fold_128(x3, get_simd_128(&mut data), k1k2);
+        x2 = fold_128(x2, get_simd_128(&mut data), k1k2);
+        x1 = fold_128(x1, get_simd_128(&mut data), k1k2);
+        x0 = fold_128(x0, get_simd_128(&mut data), k1k2);
+    }
+
+    //fold into 128 bits
+    let k3k4 = vld1q_u64([constants[2], constants[3]].as_ptr());
+    let mut x = fold_128(x3, x2, k3k4);
+    x = fold_128(x, x1, k3k4);
+    x = fold_128(x, x0, k3k4);
+
+    // fold 1*128 bits
+    while data.len() >= 16 {
+        x = fold_128(x, get_simd_128(&mut data), k3k4);
+    }
+
+    // fold 128 bits to 64 bits
+    let x2 = vreinterpretq_u64_u8(vextq_u8(vreinterpretq_u8_u64(x), vdupq_n_u8(0), 8));
+    x = veorq_u64(pmull_01(x, k3k4), x2);
+
+    /*
+     * Barret reduce to 64-bits.
+     */
+    let pu = vld1q_u64([constants[4], constants[5]].as_ptr());
+
+    let x1 = pmull_00(x, pu);
+    let x2 = pmull_01(x1, pu);
+    let x1 = vreinterpretq_u64_u8(vextq_u8(vdupq_n_u8(0), vreinterpretq_u8_u64(x1), 8));
+    x = veorq_u64(x, veorq_u64(x2, x1));
+
+    (!vgetq_lane_u64(x, 1), data)
+}
@@ -0,0 +1,5 @@
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod x86;
+
+#[cfg(target_arch = "aarch64")]
+pub mod arm;
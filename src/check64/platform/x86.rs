@@ -0,0 +1,69 @@
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::{
+    _mm_clmulepi64_si128, _mm_extract_epi32, _mm_set_epi64x, _mm_slli_si128, _mm_srli_si128,
+    _mm_xor_si128,
+};
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::{
+    _mm_clmulepi64_si128, _mm_extract_epi32, _mm_set_epi64x, _mm_slli_si128, _mm_srli_si128,
+    _mm_xor_si128,
+};
+
+use crate::check32::platform::x86::{fold_128, get_simd_128};
+
+/// This function computes the crc64 values using the same folding approach as
+/// [compute_crc](crate::check32::platform::x86::compute_crc), followed by a 64 bit barrett reduction.
+/// The constants are expected in the order generated by `CustomCrc64::generate_simd_reflected_constants`
+#[target_feature(enable = "sse4.1", enable = "pclmulqdq", enable = "sse2")]
+pub(crate) unsafe fn compute_crc64(
+    prev_crc: u64,
+    constants: [u64; 6],
+    mut data: &[u8],
+) -> (u64, &[u8]) {
+    if data.len() < 128 {
+        return (prev_crc, data);
+    }
+
+    // this is safe since we already validated we have at least 128 bytes
+    let mut x3 = get_simd_128(&mut data);
+    let mut x2 = get_simd_128(&mut data);
+    let mut x1 = get_simd_128(&mut data);
+    let mut x0 = get_simd_128(&mut data);
+
+    x3 = _mm_xor_si128(x3, _mm_set_epi64x(0, !prev_crc as i64));
+
+    let k1k2 = _mm_set_epi64x(constants[1] as i64, constants[0] as i64);
+    // fold 4*128 bits
+    while data.len() >= 64 {
+        x3 = fold_128(x3, get_simd_128(&mut data), k1k2);
+        x2 = fold_128(x2, get_simd_128(&mut data), k1k2);
+        x1 = fold_128(x1, get_simd_128(&mut data), k1k2);
+        x0 = fold_128(x0, get_simd_128(&mut data), k1k2);
+    }
+
+    //fold into 128 bits
+    let k3k4 = _mm_set_epi64x(constants[3] as i64, constants[2] as i64);
+    let mut x = fold_128(x3, x2, k3k4);
+    x = fold_128(x, x1, k3k4);
+    x = fold_128(x, x0, k3k4);
+
+    // fold 1*128 bits
+    while data.len() >= 16 {
+        x = fold_128(x, get_simd_128(&mut data), k3k4);
+    }
+
+    // fold 128 bits to 64 bits
+    x = _mm_xor_si128(_mm_clmulepi64_si128(x, k3k4, 0x10), _mm_srli_si128(x, 8));
+
+    // Barret reduce to 64-bits
+    let pu = _mm_set_epi64x(constants[5] as i64, constants[4] as i64);
+
+    let x1 = _mm_clmulepi64_si128(x, pu, 0x00);
+    let x2 = _mm_clmulepi64_si128(x1, pu, 0x10);
+    x = _mm_xor_si128(x, _mm_xor_si128(x2, _mm_slli_si128(x1, 8)));
+
+    let crc = (_mm_extract_epi32(x, 3) as u32 as u64) << 32 | _mm_extract_epi32(x, 2) as u32 as u64;
+
+    (!crc, data)
+}
@@ -0,0 +1,28 @@
+use crate::catalogue::{CRC_8_AUTOSAR, CRC_8_MAXIM_DOW, CRC_8_SMBUS};
+use crate::crc::fixed_crc;
+
+fixed_crc! {
+    /// CRC-8/SMBUS, used by the system management bus packet error code
+    Crc8Smbus(u8, CRC_8_SMBUS)
+}
+
+fixed_crc! {
+    /// CRC-8/MAXIM, also known as CRC-8/MAXIM-DOW, used by 1-wire devices
+    Crc8Maxim(u8, CRC_8_MAXIM_DOW)
+}
+
+fixed_crc! {
+    /// CRC-8/AUTOSAR
+    Crc8Autosar(u8, CRC_8_AUTOSAR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check8::CustomCrc8;
+    use crate::test_util::test_fixed_crc;
+
+    test_fixed_crc!(test_crc8_smbus, Crc8Smbus, CustomCrc8, CRC_8_SMBUS);
+    test_fixed_crc!(test_crc8_maxim, Crc8Maxim, CustomCrc8, CRC_8_MAXIM_DOW);
+    test_fixed_crc!(test_crc8_autosar, Crc8Autosar, CustomCrc8, CRC_8_AUTOSAR);
+}
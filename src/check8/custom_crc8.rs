@@ -0,0 +1,66 @@
+#[cfg(feature = "hardware")]
+use crate::check32::{select_simd, SimdConstants};
+
+use crate::backend::Backend;
+use crate::crc::sealed::SimdKernels;
+use crate::crc::{ComputeFn, Crc};
+
+/// Crc8 for any polynomial and crc parameters, see [Crc] for the constructors
+pub type CustomCrc8 = Crc<u8>;
+
+impl SimdKernels for u8 {
+    #[cfg(feature = "hardware")]
+    type Constants = SimdConstants;
+    #[cfg(not(feature = "hardware"))]
+    type Constants = ();
+
+    #[cfg(feature = "hardware")]
+    fn constants(polynomial: u8, refin: bool) -> SimdConstants {
+        SimdConstants::new(polynomial as u32, 8, refin)
+    }
+
+    #[cfg(not(feature = "hardware"))]
+    fn constants(_polynomial: u8, _refin: bool) {}
+
+    #[cfg(feature = "hardware")]
+    fn select(refin: bool, backend: Backend) -> Option<ComputeFn<u8>> {
+        select_simd(refin, backend)
+    }
+
+    #[cfg(not(feature = "hardware"))]
+    fn select(_refin: bool, _backend: Backend) -> Option<ComputeFn<u8>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::CrcDigest;
+    use crate::params::CrcParams;
+
+    #[test]
+    fn test_custom_crc8_simd() {
+        let data: Vec<u8> = (0..3000u32).map(|i| (i * 31 + (i >> 7)) as u8).collect();
+        for refin in [false, true] {
+            let params = CrcParams {
+                width: 8,
+                poly: 0x9B,
+                init: 0,
+                refin,
+                refout: refin,
+                xorout: 0,
+                check: 0,
+                residue: 0,
+            };
+            let mut expected = CustomCrc8::new_naive_with_params(params);
+            expected.update(&data);
+            for backend in CustomCrc8::supported_backends(params) {
+                let mut crc = CustomCrc8::new_with_backend(params, backend).unwrap();
+                crc.update(&data[..1]);
+                crc.update(&data[1..]);
+                assert_eq!(crc.digest(), expected.digest(), "{:?}", backend);
+            }
+        }
+    }
+}
@@ -0,0 +1,10 @@
+mod crc8;
+mod custom_crc8;
+
+use crate::crc::CrcCombiner;
+
+/// Combines crc8s, see [CrcCombiner]
+pub type Crc8Combiner = CrcCombiner<u8>;
+
+pub use crc8::{Crc8Autosar, Crc8Maxim, Crc8Smbus};
+pub use custom_crc8::CustomCrc8;
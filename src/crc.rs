@@ -0,0 +1,1470 @@
+//! Width agnostic crc engine
+//!
+//! [Crc] implements the naive and slice by 16 table lookup approaches for every [CrcWidth], while
+//! the simd approaches are provided per width. [CustomCrc32](crate::check32::CustomCrc32) and
+//! [CustomCrc64](crate::check64::CustomCrc64) are aliases of `Crc<u32>` and `Crc<u64>`.
+//!
+//! Every crc type implements [CrcDigest], so code can be written generic over the crc width.
+//!
+//! ```
+//! use librscrc::prelude::*;
+//!
+//! fn checksum<D: CrcDigest>(mut crc: D, data: &[u8]) -> D::Output {
+//!     crc.update(data);
+//!     crc.digest()
+//! }
+//!
+//! assert_eq!(checksum(Crc32C::new_simd(), b"123456789"), 0xE3069283);
+//! assert_eq!(checksum(Crc64ECMA::new_simd(), b"123456789"), 0x995DC9BBDF1939FA);
+//!
+//! let crc_16_arc = CrcParams {
+//!     width: 16,
+//!     poly: 0x8005,
+//!     init: 0x0000,
+//!     refin: true,
+//!     refout: true,
+//!     xorout: 0x0000,
+//!     check: 0xBB3D,
+//!     residue: 0x0000,
+//! };
+//! assert_eq!(checksum(Crc::<u16>::with_params(crc_16_arc), b"123456789"), 0xBB3D);
+//! ```
+
+use core::fmt::Debug;
+use core::hash::{Hash, Hasher};
+
+use crate::backend::{Backend, UnsupportedBackend};
+use crate::params::CrcParams;
+use sealed::SimdKernels;
+
+#[cfg(feature = "parallel")]
+use crate::parallel;
+
+/// A crc which can be updated with data and queried for its digest
+pub trait CrcDigest {
+    /// Type of the digest, the unsigned integer holding the crc
+    type Output;
+
+    /// Update digest with data
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the crc digest
+    fn digest(&self) -> Self::Output;
+
+    /// Updates digest with `len` zero bytes, e.g. for the holes of a sparse file. The crc types
+    /// of this crate advance the crc in O(log len) through `x^(8 * len) mod P`, while the default
+    /// feeds a buffer of zeros to [update](Self::update).
+    fn update_zeros(&mut self, len: u64) {
+        let zeros = [0u8; 4096];
+        let mut remaining = len;
+        while remaining > 0 {
+            let chunk = remaining.min(zeros.len() as u64) as usize;
+            self.update(&zeros[..chunk]);
+            remaining -= chunk as u64;
+        }
+    }
+}
+
+/// Unsigned integer types holding the crc register of a [Crc]. Implemented for `u8`, `u16`, `u32`
+/// and `u64`.
+pub trait CrcWidth:
+    Copy + Eq + Hash + Debug + Default + Into<u64> + Send + Sync + 'static + sealed::Width
+{
+    /// Width of the crc in bits
+    const BITS: u8;
+}
+
+pub(crate) type ComputeFn<W> = fn(&Crc<W>, W, &[u8]) -> W;
+
+pub(crate) mod sealed {
+    use super::{ComputeFn, CrcCombiner, CrcWidth};
+    use crate::backend::Backend;
+    use core::ops::{BitAnd, BitXor, Not};
+
+    /// Width specific operations used by the generic parts of [Crc](super::Crc). The kernels take
+    /// and return the complement of the crc register.
+    pub trait Width:
+        Sized + Not<Output = Self> + BitXor<Output = Self> + BitAnd<Output = Self>
+    {
+        /// Constants used by the simd kernels of this width
+        type SimdConstants: Copy + Send + Sync;
+
+        fn from_u64(value: u64) -> Self;
+        fn reverse_bits(self) -> Self;
+        fn naive(prev_crc: Self, polynomial: Self, data: &[u8]) -> Self;
+        fn msb_naive(prev_crc: Self, polynomial: Self, data: &[u8]) -> Self;
+        fn lookup(prev_crc: Self, lookup_table: &[[Self; 256]; 16], data: &[u8]) -> Self;
+        fn msb_lookup(prev_crc: Self, lookup_table: &[[Self; 256]; 16], data: &[u8]) -> Self;
+        fn lookup_table_16(polynomial: Self) -> [[Self; 256]; 16];
+        fn msb_lookup_table_16(polynomial: Self) -> [[Self; 256]; 16];
+        fn multiply_mod(a: Self, b: Self, polynomial: Self) -> Self;
+        fn x8n_mod(len: u64, polynomial: Self) -> Self;
+        fn combine(combiner: &CrcCombiner<Self>, crc_a: Self, crc_b: Self) -> Self;
+        fn append_zeros(combiner: &CrcCombiner<Self>, crc_a: Self) -> Self;
+
+        fn simd_constants(polynomial: Self, refin: bool) -> Self::SimdConstants;
+
+        /// Returns the simd kernel for the backend, if the width implements it for the bit order
+        /// and the cpu supports it
+        fn select_simd(refin: bool, backend: Backend) -> Option<ComputeFn<Self>>
+        where
+            Self: CrcWidth;
+    }
+
+    /// Simd kernels of a width, implemented next to the kernels of each width
+    pub trait SimdKernels: Sized {
+        type Constants: Copy + Send + Sync;
+
+        fn constants(polynomial: Self, refin: bool) -> Self::Constants;
+
+        fn select(refin: bool, backend: Backend) -> Option<ComputeFn<Self>>
+        where
+            Self: CrcWidth;
+    }
+}
+
+/// Crc engine for any [CrcWidth] and [CrcParams]
+///
+/// The backend is selected once at construction, see [Backend] for the available approaches.
+#[derive(Clone)]
+pub struct Crc<W: CrcWidth> {
+    pub(crate) params: CrcParams,
+    pub(crate) polynomial: W,
+    #[cfg_attr(not(feature = "hardware"), allow(dead_code))]
+    pub(crate) simd_constants: W::SimdConstants,
+    pub(crate) lookup_table: [[W; 256]; 16],
+    pub(crate) compute: ComputeFn<W>,
+    backend: Backend,
+    pub(crate) state: W,
+}
+
+impl<W: CrcWidth> Crc<W> {
+    /// Creates a new `Crc` using naive approach, for the reflected polynomial with the register
+    /// initialized to and xored with all ones
+    pub fn new_naive(polynomial: W) -> Self {
+        Self::new_naive_with_params(Self::default_params(polynomial))
+    }
+
+    /// Creates a new `Crc` using a table lookup approach, for the reflected polynomial with the
+    /// register initialized to and xored with all ones
+    pub fn new_lookup(polynomial: W) -> Self {
+        Self::new_lookup_with_params(Self::default_params(polynomial))
+    }
+
+    #[cfg(feature = "hardware")]
+    /// Creates a new `Crc` using simd intrinsics based on
+    /// [intel's paper](https://www.intel.com/content/dam/www/public/us/en/documents/white-papers/fast-crc-computation-generic-polynomials-pclmulqdq-paper.pdf),
+    /// for the reflected polynomial with the register initialized to and xored with all ones.
+    /// Bits of the polynomial above the crc width are ignored.
+    /// - See [Backend] for the cpu features required by each simd backend
+    /// - Otherwise defaults to table lookup approach
+    pub fn new_simd(polynomial: u64) -> Self {
+        Self::new_simd_with_params(Self::default_params(W::from_u64(polynomial)))
+    }
+
+    /// Creates a new `Crc` for the given crc parameters, using the simd approach when the
+    /// `hardware` feature is enabled and the table lookup approach otherwise.
+    ///
+    /// Panics if `params.width` does not match the width of `W`.
+    pub fn with_params(params: CrcParams) -> Self {
+        #[cfg(feature = "hardware")]
+        return Self::new_simd_with_params(params);
+        #[cfg(not(feature = "hardware"))]
+        return Self::new_lookup_with_params(params);
+    }
+
+    /// Creates a new `Crc` for the given crc parameters using naive approach
+    ///
+    /// Panics if `params.width` does not match the width of `W`.
+    pub fn new_naive_with_params(params: CrcParams) -> Self {
+        assert_eq!(
+            params.width,
+            W::BITS,
+            "crc width does not match the {} bit register",
+            W::BITS
+        );
+        let polynomial = W::from_u64(params.poly);
+        let (lookup_table, compute): (_, ComputeFn<W>) = if params.refin {
+            (W::lookup_table_16(polynomial), Self::compute_naive)
+        } else {
+            (W::msb_lookup_table_16(polynomial), Self::compute_msb_naive)
+        };
+        Self {
+            params,
+            polynomial,
+            simd_constants: W::simd_constants(polynomial, params.refin),
+            lookup_table,
+            compute,
+            backend: Backend::Naive,
+            state: Self::initial_state(&params),
+        }
+    }
+
+    /// Creates a new `Crc` for the given crc parameters using a table lookup approach
+    ///
+    /// Panics if `params.width` does not match the width of `W`.
+    pub fn new_lookup_with_params(params: CrcParams) -> Self {
+        let mut crc = Self::new_naive_with_params(params);
+        if params.refin {
+            crc.compute = Self::compute_lookup;
+        } else {
+            crc.compute = Self::compute_msb_lookup;
+        }
+        crc.backend = Backend::Lookup;
+        crc
+    }
+
+    #[cfg(feature = "hardware")]
+    /// Creates a new `Crc` for the given crc parameters using simd intrinsics.
+    /// See [new_simd](Self::new_simd) for the cpu features required.
+    ///
+    /// Panics if `params.width` does not match the width of `W`.
+    pub fn new_simd_with_params(params: CrcParams) -> Self {
+        let mut crc = Self::new_lookup_with_params(params);
+        let simd = Backend::SIMD
+            .into_iter()
+            .find_map(|backend| Some((W::select_simd(params.refin, backend)?, backend)));
+        if let Some((compute, backend)) = simd {
+            crc.compute = compute;
+            crc.backend = backend;
+        }
+        crc
+    }
+
+    /// Creates a new `Crc` for the given crc parameters using the given backend. Unlike the other
+    /// constructors this does not fall back to a slower backend, and fails if the backend is not
+    /// supported.
+    ///
+    /// Panics if `params.width` does not match the width of `W`.
+    pub fn new_with_backend(
+        params: CrcParams,
+        backend: Backend,
+    ) -> Result<Self, UnsupportedBackend> {
+        let compute = Self::select(params.refin, backend).ok_or(UnsupportedBackend(backend))?;
+        let mut crc = Self::new_naive_with_params(params);
+        crc.compute = compute;
+        crc.backend = backend;
+        Ok(crc)
+    }
+
+    /// Returns the backends `Crc` supports for the given crc parameters on this cpu with the
+    /// enabled crate features
+    pub fn supported_backends(params: CrcParams) -> impl Iterator<Item = Backend> {
+        Backend::ALL
+            .into_iter()
+            .filter(move |&backend| Self::select(params.refin, backend).is_some())
+    }
+
+    /// Returns the crc parameters used by this instance
+    pub fn params(&self) -> CrcParams {
+        self.params
+    }
+
+    /// Returns the backend this instance computes crcs with, after falling back from
+    /// unsupported cpu features
+    pub fn selected_backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Resets the crc to its initial state, keeping the selected backend
+    pub fn reset(&mut self) {
+        self.state = Self::initial_state(&self.params);
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Updates digest with data split across up to `threads` threads, each using the approach
+    /// this instance was created with. The partial crcs are merged using [combine](Self::combine).
+    pub fn update_parallel(&mut self, data: &[u8], threads: usize) {
+        let empty = Self::initial_state(&self.params);
+        self.state = parallel::update_parallel(
+            self.state,
+            data,
+            threads,
+            empty,
+            |state, data| (self.compute)(self, state, data),
+            |state_a, state_b, len_b| {
+                let crc = self.combine(self.digest_of(state_a), self.digest_of(state_b), len_b);
+                self.state_of(crc)
+            },
+        );
+    }
+
+    /// Computes the crc of `A || B` given `crc_a`, `crc_b` and the length of `B` in bytes
+    pub fn combine(&self, crc_a: W, crc_b: W, len_b: u64) -> W {
+        W::combine(&self.combiner(len_b), crc_a, crc_b)
+    }
+
+    /// Creates a [CrcCombiner] for combining crcs where the second chunk is `len_b` bytes long
+    pub fn combiner(&self, len_b: u64) -> CrcCombiner<W> {
+        CrcCombiner::new(self.params, len_b)
+    }
+
+    /// Computes the crc of data after replacing `old` at `offset` with `new`, given `old_crc`, the
+    /// crc of the original `total_len` bytes. Takes O(len(old) + log(total_len)) instead of
+    /// checksumming the whole data again.
+    ///
+    /// # Panics
+    /// Panics if `old` and `new` differ in length or do not fit in `total_len` at `offset`.
+    pub fn patch(&self, old_crc: W, total_len: u64, offset: u64, old: &[u8], new: &[u8]) -> W {
+        let update = |state, data: &[u8]| (self.compute)(self, state, data);
+        patch(self.params, update, old_crc, total_len, offset, old, new)
+    }
+
+    /// Returns the `W::BITS / 8` bytes, as a little endian integer, which written over the data at
+    /// `offset` make its crc equal to `target`. Used by the width specific `forge` methods.
+    pub(crate) fn forge_bytes(&self, data: &[u8], offset: usize, target: W) -> Option<u64> {
+        let update = |state, data: &[u8]| (self.compute)(self, state, data);
+        let initial_state = Self::initial_state(&self.params);
+        forge(self.params, update, initial_state, data, offset, target)
+    }
+
+    fn default_params(polynomial: W) -> CrcParams {
+        let all_ones = !W::default();
+        CrcParams {
+            width: W::BITS,
+            poly: polynomial.into(),
+            init: all_ones.into(),
+            refin: true,
+            refout: true,
+            xorout: all_ones.into(),
+            check: 0,
+            residue: 0,
+        }
+    }
+
+    /// The kernels take and return the complement of the crc register, so the state is kept in
+    /// that form and `init` is reflected for lsb first kernels.
+    fn initial_state(params: &CrcParams) -> W {
+        let init = W::from_u64(params.init);
+        if params.refin {
+            !init.reverse_bits()
+        } else {
+            !init
+        }
+    }
+
+    /// Converts the state used by the kernels into the digest
+    pub(crate) fn digest_of(&self, state: W) -> W {
+        let crc = !state;
+        let crc = if self.params.refin != self.params.refout {
+            crc.reverse_bits()
+        } else {
+            crc
+        };
+        crc ^ W::from_u64(self.params.xorout)
+    }
+
+    /// Converts a digest into the state used by the kernels
+    pub(crate) fn state_of(&self, crc: W) -> W {
+        let crc = crc ^ W::from_u64(self.params.xorout);
+        let crc = if self.params.refin != self.params.refout {
+            crc.reverse_bits()
+        } else {
+            crc
+        };
+        !crc
+    }
+
+    fn select(refin: bool, backend: Backend) -> Option<ComputeFn<W>> {
+        match (backend, refin) {
+            (Backend::Naive, true) => Some(Self::compute_naive),
+            (Backend::Naive, false) => Some(Self::compute_msb_naive),
+            (Backend::Lookup, true) => Some(Self::compute_lookup),
+            (Backend::Lookup, false) => Some(Self::compute_msb_lookup),
+            _ => W::select_simd(refin, backend),
+        }
+    }
+
+    fn compute_naive(&self, prev_crc: W, data: &[u8]) -> W {
+        W::naive(prev_crc, self.polynomial, data)
+    }
+
+    fn compute_lookup(&self, prev_crc: W, data: &[u8]) -> W {
+        W::lookup(prev_crc, &self.lookup_table, data)
+    }
+
+    fn compute_msb_naive(&self, prev_crc: W, data: &[u8]) -> W {
+        W::msb_naive(prev_crc, self.polynomial, data)
+    }
+
+    fn compute_msb_lookup(&self, prev_crc: W, data: &[u8]) -> W {
+        W::msb_lookup(prev_crc, &self.lookup_table, data)
+    }
+}
+
+impl<W: CrcWidth> CrcDigest for Crc<W> {
+    type Output = W;
+
+    fn update(&mut self, data: &[u8]) {
+        self.state = (self.compute)(self, self.state, data);
+    }
+
+    fn digest(&self) -> W {
+        self.digest_of(self.state)
+    }
+
+    fn update_zeros(&mut self, len: u64) {
+        let crc = W::append_zeros(&self.combiner(len), self.digest_of(self.state));
+        self.state = self.state_of(crc);
+    }
+}
+
+impl<W: CrcWidth> Hasher for Crc<W> {
+    fn finish(&self) -> u64 {
+        self.digest().into()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+}
+
+/// Combines the crcs of two consecutive chunks `A` and `B` into the crc of `A || B`, similar to
+/// zlib's `crc32_combine`.
+///
+/// The operator `x^(8 * len(B)) mod P` is computed once, so a combiner can be reused for every
+/// chunk of the same length. [combine](Self::combine) and [append_zeros](Self::append_zeros) are
+/// `const fn`, implemented for each [CrcWidth].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcCombiner<W> {
+    params: CrcParams,
+    operator: W,
+}
+
+impl<W: CrcWidth> CrcCombiner<W> {
+    pub(crate) fn new(params: CrcParams, len_b: u64) -> Self {
+        Self {
+            params,
+            operator: W::x8n_mod(len_b, W::from_u64(params.poly)),
+        }
+    }
+}
+
+/// Patches `old_crc`, the crc of `total_len` bytes, after `old` at `offset` was replaced with
+/// `new`. As the crc is affine, the crc changes by the crc of `old ^ new` followed by the bytes
+/// after the edit, computed with a zero `init` and `xorout`. `update` is the kernel of the crc
+/// type, which takes and returns the complemented register.
+pub(crate) fn patch<W: CrcWidth>(
+    params: CrcParams,
+    mut update: impl FnMut(W, &[u8]) -> W,
+    old_crc: W,
+    total_len: u64,
+    offset: u64,
+    old: &[u8],
+    new: &[u8],
+) -> W {
+    assert_eq!(
+        old.len(),
+        new.len(),
+        "old and new must have the same length"
+    );
+    let end = offset.checked_add(old.len() as u64);
+    assert!(
+        matches!(end, Some(end) if end <= total_len),
+        "the patched bytes must be within the data"
+    );
+
+    let mut state = !W::default();
+    let mut delta = [0u8; 256];
+    for (old, new) in old.chunks(delta.len()).zip(new.chunks(delta.len())) {
+        for ((d, o), n) in delta.iter_mut().zip(old).zip(new) {
+            *d = o ^ n;
+        }
+        state = update(state, &delta[..old.len()]);
+    }
+    let crc_delta = if params.refin != params.refout {
+        (!state).reverse_bits()
+    } else {
+        !state
+    };
+
+    let delta_params = CrcParams {
+        init: 0,
+        xorout: 0,
+        ..params
+    };
+    let trailing = total_len - offset - old.len() as u64;
+    old_crc ^ W::append_zeros(&CrcCombiner::new(delta_params, trailing), crc_delta)
+}
+
+/// Finds the `W::BITS / 8` bytes which, written over `data` at `offset`, give the crc `target`.
+/// The crc of the data with those bytes zeroed is computed first, starting from `initial_state`.
+/// Every bit of the replacement then changes the crc by a fixed delta, so the bits are solved for
+/// with gaussian elimination over the deltas. `update` is the kernel of the crc type, which takes
+/// and returns the complemented register. Returns the bytes as a little endian integer, or `None`
+/// if the deltas do not span every crc, which happens for polynomials without the x^0 term.
+///
+/// Panics if the forged bytes do not fit in `data` at `offset`.
+pub(crate) fn forge<W: CrcWidth>(
+    params: CrcParams,
+    mut update: impl FnMut(W, &[u8]) -> W,
+    initial_state: W,
+    data: &[u8],
+    offset: usize,
+    target: W,
+) -> Option<u64> {
+    let bits = W::BITS as usize;
+    let zeros = [0u8; 8];
+    let end = offset
+        .checked_add(bits / 8)
+        .filter(|&end| end <= data.len())
+        .expect("the forged bytes must be within the data");
+    let mut state = update(initial_state, &data[..offset]);
+    state = update(state, &zeros[..bits / 8]);
+    state = update(state, &data[end..]);
+    let crc_zeros = if params.refin != params.refout {
+        (!state).reverse_bits()
+    } else {
+        !state
+    } ^ W::from_u64(params.xorout);
+
+    let (total_len, offset) = (data.len() as u64, offset as u64);
+    // basis[b] holds a combination of deltas whose highest set bit is b, along with the
+    // replacement bits producing it
+    let mut basis: [Option<(u64, u64)>; 64] = [None; 64];
+    for bit in 0..bits {
+        let mut replacement = zeros;
+        replacement[bit / 8] = 1 << (bit % 8);
+        let (zeros, replacement) = (&zeros[..bits / 8], &replacement[..bits / 8]);
+        let delta = patch(
+            params,
+            &mut update,
+            W::default(),
+            total_len,
+            offset,
+            zeros,
+            replacement,
+        );
+
+        let (mut delta, mut combination) = (delta.into(), 1u64 << bit);
+        while delta != 0 {
+            let high = 63 - delta.leading_zeros() as usize;
+            match basis[high] {
+                Some((other, other_combination)) => {
+                    delta ^= other;
+                    combination ^= other_combination;
+                }
+                None => {
+                    basis[high] = Some((delta, combination));
+                    break;
+                }
+            }
+        }
+    }
+
+    let (mut needed, mut combination): (u64, u64) = ((target ^ crc_zeros).into(), 0);
+    while needed != 0 {
+        let high = 63 - needed.leading_zeros() as usize;
+        let (other, other_combination) = basis[high]?;
+        needed ^= other;
+        combination ^= other_combination;
+    }
+    Some(combination)
+}
+
+/// Implements [CrcWidth] for an unsigned integer type, along with the const kernels and table
+/// generators shared by every width. The const versions are used to build the tables of the
+/// fixed algorithms at compile time.
+macro_rules! impl_crc_width {
+    ($ty:ty) => {
+        impl CrcWidth for $ty {
+            const BITS: u8 = <$ty>::BITS as u8;
+        }
+
+        impl sealed::Width for $ty {
+            type SimdConstants = <$ty as SimdKernels>::Constants;
+
+            fn from_u64(value: u64) -> Self {
+                value as $ty
+            }
+
+            fn reverse_bits(self) -> Self {
+                <$ty>::reverse_bits(self)
+            }
+
+            fn naive(prev_crc: Self, polynomial: Self, data: &[u8]) -> Self {
+                Crc::<$ty>::naive(prev_crc, polynomial, data)
+            }
+
+            fn msb_naive(prev_crc: Self, polynomial: Self, data: &[u8]) -> Self {
+                Crc::<$ty>::msb_naive(prev_crc, polynomial, data)
+            }
+
+            fn lookup(prev_crc: Self, lookup_table: &[[Self; 256]; 16], data: &[u8]) -> Self {
+                Crc::<$ty>::lookup(prev_crc, lookup_table, data)
+            }
+
+            fn msb_lookup(prev_crc: Self, lookup_table: &[[Self; 256]; 16], data: &[u8]) -> Self {
+                Crc::<$ty>::msb_lookup(prev_crc, lookup_table, data)
+            }
+
+            fn lookup_table_16(polynomial: Self) -> [[Self; 256]; 16] {
+                Crc::<$ty>::generate_lookup_table_16(polynomial)
+            }
+
+            fn msb_lookup_table_16(polynomial: Self) -> [[Self; 256]; 16] {
+                Crc::<$ty>::generate_msb_lookup_table_16(polynomial)
+            }
+
+            fn multiply_mod(a: Self, b: Self, polynomial: Self) -> Self {
+                Crc::<$ty>::multiply_mod(a, b, polynomial)
+            }
+
+            fn x8n_mod(len: u64, polynomial: Self) -> Self {
+                Crc::<$ty>::x8n_mod(len, polynomial)
+            }
+
+            fn combine(combiner: &CrcCombiner<Self>, crc_a: Self, crc_b: Self) -> Self {
+                combiner.combine(crc_a, crc_b)
+            }
+
+            fn append_zeros(combiner: &CrcCombiner<Self>, crc_a: Self) -> Self {
+                combiner.append_zeros(crc_a)
+            }
+
+            fn simd_constants(polynomial: Self, refin: bool) -> Self::SimdConstants {
+                <$ty as SimdKernels>::constants(polynomial, refin)
+            }
+
+            fn select_simd(refin: bool, backend: Backend) -> Option<ComputeFn<Self>> {
+                <$ty as SimdKernels>::select(refin, backend)
+            }
+        }
+
+        impl CrcCombiner<$ty> {
+            /// Computes the crc of `A || B` given `crc_a` and `crc_b`
+            pub const fn combine(&self, crc_a: $ty, crc_b: $ty) -> $ty {
+                let register_a = self.register_of(crc_a) ^ self.params.init as $ty;
+                let register_b = self.register_of(crc_b);
+                let polynomial = self.params.poly as $ty;
+                let register = Crc::<$ty>::multiply_mod(register_a, self.operator, polynomial);
+                self.digest_of(register ^ register_b)
+            }
+
+            /// Computes the crc of `A || 0^len(B)`, i.e. `A` followed by as many zero bytes as
+            /// `B`, given `crc_a`
+            pub const fn append_zeros(&self, crc_a: $ty) -> $ty {
+                let register_a = self.register_of(crc_a);
+                let polynomial = self.params.poly as $ty;
+                let register = Crc::<$ty>::multiply_mod(register_a, self.operator, polynomial);
+                self.digest_of(register)
+            }
+
+            /// Converts a digest into the msb first crc register it was produced from
+            const fn register_of(&self, crc: $ty) -> $ty {
+                let register = crc ^ self.params.xorout as $ty;
+                if self.params.refout {
+                    register.reverse_bits()
+                } else {
+                    register
+                }
+            }
+
+            const fn digest_of(&self, register: $ty) -> $ty {
+                let crc = if self.params.refout {
+                    register.reverse_bits()
+                } else {
+                    register
+                };
+                crc ^ self.params.xorout as $ty
+            }
+        }
+
+        // not every width uses every helper, e.g. only the simd widths need barrett_quotient
+        #[allow(dead_code)]
+        impl Crc<$ty> {
+            const BITS: u32 = <$ty>::BITS;
+
+            const fn shr8(value: $ty) -> $ty {
+                ((value as u64) >> 8) as $ty
+            }
+
+            const fn shl8(value: $ty) -> $ty {
+                ((value as u64) << 8) as $ty
+            }
+
+            pub(crate) const fn naive(prev_crc: $ty, polynomial: $ty, data: &[u8]) -> $ty {
+                let mut crc = !prev_crc;
+                let polynomial = polynomial.reverse_bits();
+                let mut i = 0;
+                let mut j = 0;
+                while i < data.len() {
+                    crc ^= data[i] as $ty;
+
+                    while j < 8 {
+                        if crc & 1 == 1 {
+                            crc = crc >> 1 ^ polynomial;
+                        } else {
+                            crc >>= 1;
+                        }
+                        j += 1;
+                    }
+                    j = 0;
+                    i += 1;
+                }
+
+                !crc
+            }
+
+            pub(crate) const fn msb_naive(prev_crc: $ty, polynomial: $ty, data: &[u8]) -> $ty {
+                let mut crc = !prev_crc;
+                let mut i = 0;
+                let mut j = 0;
+                while i < data.len() {
+                    crc ^= (data[i] as $ty) << (Self::BITS - 8);
+
+                    while j < 8 {
+                        if crc >> (Self::BITS - 1) == 1 {
+                            crc = crc << 1 ^ polynomial;
+                        } else {
+                            crc <<= 1;
+                        }
+                        j += 1;
+                    }
+                    j = 0;
+                    i += 1;
+                }
+
+                !crc
+            }
+
+            /// Slice by 16 table lookup, the first bytes of every 16 byte block are xored with the
+            /// crc register starting from its least significant byte
+            pub(crate) fn lookup(
+                prev_crc: $ty,
+                lookup_table: &[[$ty; 256]; 16],
+                mut data: &[u8],
+            ) -> $ty {
+                let mut crc = !prev_crc;
+
+                while data.len() >= 16 {
+                    let mut block = [0u8; 16];
+                    block.copy_from_slice(&data[..16]);
+                    for (b, c) in block.iter_mut().zip(crc.to_le_bytes()) {
+                        *b ^= c;
+                    }
+                    let mut next = 0;
+                    for (i, &b) in block.iter().enumerate() {
+                        next ^= lookup_table[15 - i][b as usize];
+                    }
+                    crc = next;
+                    data = &data[16..];
+                }
+                for &b in data {
+                    crc = lookup_table[0][((crc as u8) ^ b) as usize] ^ Self::shr8(crc);
+                }
+
+                !crc
+            }
+
+            /// Slice by 16 table lookup for msb first crcs, the first bytes of every 16 byte block
+            /// are xored with the crc register starting from its most significant byte
+            pub(crate) fn msb_lookup(
+                prev_crc: $ty,
+                lookup_table: &[[$ty; 256]; 16],
+                mut data: &[u8],
+            ) -> $ty {
+                let mut crc = !prev_crc;
+
+                while data.len() >= 16 {
+                    let mut block = [0u8; 16];
+                    block.copy_from_slice(&data[..16]);
+                    for (b, c) in block.iter_mut().zip(crc.to_be_bytes()) {
+                        *b ^= c;
+                    }
+                    let mut next = 0;
+                    for (i, &b) in block.iter().enumerate() {
+                        next ^= lookup_table[15 - i][b as usize];
+                    }
+                    crc = next;
+                    data = &data[16..];
+                }
+                for &b in data {
+                    let index = ((crc >> (Self::BITS - 8)) as u8 ^ b) as usize;
+                    crc = lookup_table[0][index] ^ Self::shl8(crc);
+                }
+
+                !crc
+            }
+
+            /// Byte at a time table lookup which, unlike [lookup](Self::lookup), can be
+            /// evaluated in const contexts
+            pub(crate) const fn const_lookup(
+                prev_crc: $ty,
+                lookup_table: &[$ty; 256],
+                data: &[u8],
+            ) -> $ty {
+                let mut crc = !prev_crc;
+                let mut i = 0;
+                while i < data.len() {
+                    crc = lookup_table[((crc as u8) ^ data[i]) as usize] ^ Self::shr8(crc);
+                    i += 1;
+                }
+
+                !crc
+            }
+
+            /// Byte at a time table lookup for msb first crcs which, unlike
+            /// [msb_lookup](Self::msb_lookup), can be evaluated in const contexts
+            pub(crate) const fn const_msb_lookup(
+                prev_crc: $ty,
+                lookup_table: &[$ty; 256],
+                data: &[u8],
+            ) -> $ty {
+                let mut crc = !prev_crc;
+                let mut i = 0;
+                while i < data.len() {
+                    let index = ((crc >> (Self::BITS - 8)) as u8 ^ data[i]) as usize;
+                    crc = lookup_table[index] ^ Self::shl8(crc);
+                    i += 1;
+                }
+
+                !crc
+            }
+
+            /// Computes the crc of `data` for any crc parameters in const contexts, e.g.
+            /// `const CRC: u16 = CustomCrc16::const_checksum(CRC_16_MODBUS, b"magic");`.
+            /// Uses the bitwise approach as building a lookup table costs more than
+            /// checksumming short data.
+            ///
+            /// # Panics
+            /// Panics if `params.width` differs from the width of the register.
+            pub const fn const_checksum(params: CrcParams, data: &[u8]) -> $ty {
+                assert!(
+                    params.width as u32 == Self::BITS,
+                    "crc width does not match the register"
+                );
+                let polynomial = params.poly as $ty;
+                let state = if params.refin {
+                    Self::naive(!(params.init as $ty).reverse_bits(), polynomial, data)
+                } else {
+                    Self::msb_naive(!(params.init as $ty), polynomial, data)
+                };
+                let crc = !state;
+                let crc = if params.refin != params.refout {
+                    crc.reverse_bits()
+                } else {
+                    crc
+                };
+                crc ^ params.xorout as $ty
+            }
+
+            pub(crate) const fn generate_lookup_table_16(polynomial: $ty) -> [[$ty; 256]; 16] {
+                let mut table = [[0; 256]; 16];
+
+                table[0] = Self::generate_lookup_table(polynomial);
+                let mut length = 0;
+                let mut j = 1;
+
+                while length < 256 {
+                    let mut crc = table[0][length];
+                    while j < 16 {
+                        crc = Self::shr8(crc) ^ table[0][crc as u8 as usize];
+                        table[j][length] = crc;
+                        j += 1;
+                    }
+                    j = 1;
+                    length += 1;
+                }
+
+                table
+            }
+
+            pub(crate) const fn generate_lookup_table(polynomial: $ty) -> [$ty; 256] {
+                let mut table = [0; 256];
+                let polynomial = polynomial.reverse_bits();
+                let mut length = 0;
+                let mut j = 0;
+
+                while length < 256 {
+                    let mut crc = length as $ty;
+                    while j < 8 {
+                        if crc & 1 == 1 {
+                            crc = (crc >> 1) ^ polynomial;
+                        } else {
+                            crc >>= 1;
+                        }
+                        j += 1;
+                    }
+                    table[length] = crc;
+                    j = 0;
+                    length += 1;
+                }
+
+                table
+            }
+
+            pub(crate) const fn generate_msb_lookup_table_16(polynomial: $ty) -> [[$ty; 256]; 16] {
+                let mut table = [[0; 256]; 16];
+
+                table[0] = Self::generate_msb_lookup_table(polynomial);
+                let mut length = 0;
+                let mut j = 1;
+
+                while length < 256 {
+                    let mut crc = table[0][length];
+                    while j < 16 {
+                        crc = Self::shl8(crc) ^ table[0][(crc >> (Self::BITS - 8)) as usize];
+                        table[j][length] = crc;
+                        j += 1;
+                    }
+                    j = 1;
+                    length += 1;
+                }
+
+                table
+            }
+
+            pub(crate) const fn generate_msb_lookup_table(polynomial: $ty) -> [$ty; 256] {
+                let mut table = [0; 256];
+                let mut length = 0;
+                let mut j = 0;
+
+                while length < 256 {
+                    let mut crc = (length as $ty) << (Self::BITS - 8);
+                    while j < 8 {
+                        if crc >> (Self::BITS - 1) == 1 {
+                            crc = (crc << 1) ^ polynomial;
+                        } else {
+                            crc <<= 1;
+                        }
+                        j += 1;
+                    }
+                    table[length] = crc;
+                    j = 0;
+                    length += 1;
+                }
+
+                table
+            }
+
+            /// Computes `a * b mod polynomial` for msb first (non reflected) values
+            pub(crate) const fn multiply_mod(a: $ty, b: $ty, polynomial: $ty) -> $ty {
+                let mut result: $ty = 0;
+                let mut i = Self::BITS;
+                while i > 0 {
+                    i -= 1;
+                    let carry = result >> (Self::BITS - 1);
+                    result = (result << 1) ^ (carry * polynomial);
+                    if (b >> i) & 1 == 1 {
+                        result ^= a;
+                    }
+                }
+                result
+            }
+
+            /// Computes `x^(8 * len) mod polynomial`
+            pub(crate) const fn x8n_mod(len: u64, polynomial: $ty) -> $ty {
+                Self::power_mod(Self::xn_mod(8, polynomial), len, polynomial)
+            }
+
+            /// Computes `x^n mod polynomial`
+            pub(crate) const fn xn_mod(n: u64, polynomial: $ty) -> $ty {
+                Self::power_mod(1 << 1, n, polynomial)
+            }
+
+            /// Computes `base^exponent mod polynomial` using square and multiply
+            const fn power_mod(mut base: $ty, mut exponent: u64, polynomial: $ty) -> $ty {
+                let mut result = 1;
+                while exponent > 0 {
+                    if exponent & 1 == 1 {
+                        result = Self::multiply_mod(result, base, polynomial);
+                    }
+                    base = Self::multiply_mod(base, base, polynomial);
+                    exponent >>= 1;
+                }
+                result
+            }
+
+            /// Computes `x^(2 * width) / polynomial`, without the implicit x^width term of the
+            /// quotient, as used by barrett reduction
+            pub(crate) const fn barrett_quotient(polynomial: $ty) -> $ty {
+                let mut remainder = polynomial;
+                let mut quotient: $ty = 0;
+                let mut i = 0;
+                while i < Self::BITS {
+                    let msb = remainder >> (Self::BITS - 1);
+                    quotient = (quotient << 1) ^ msb;
+                    remainder = (remainder << 1) ^ (msb * polynomial);
+                    i += 1;
+                }
+                quotient
+            }
+        }
+    };
+}
+
+impl_crc_width!(u8);
+impl_crc_width!(u16);
+impl_crc_width!(u32);
+impl_crc_width!(u64);
+
+/// Defines a crc type for fixed crc parameters, with the lookup tables and simd constants
+/// generated at compile time. Used for the crc8 and crc16 algorithms.
+macro_rules! fixed_crc {
+    ($(#[$attr:meta])* $name:ident($ty:ty, $params:expr)) => {
+        $(#[$attr])*
+        #[derive(Clone)]
+        pub struct $name {
+            state: $ty,
+            compute: fn($ty, &[u8]) -> $ty,
+            backend: $crate::backend::Backend,
+        }
+
+        impl $name {
+            const PARAMS: $crate::params::CrcParams = $params;
+            const POLYNOMIAL: $ty = Self::PARAMS.poly as $ty;
+            const LOOKUP_TABLE: [[$ty; 256]; 16] = if Self::PARAMS.refin {
+                $crate::crc::Crc::<$ty>::generate_lookup_table_16(Self::POLYNOMIAL)
+            } else {
+                $crate::crc::Crc::<$ty>::generate_msb_lookup_table_16(Self::POLYNOMIAL)
+            };
+            #[cfg(feature = "hardware")]
+            const SIMD_CONSTANTS: $crate::check32::SimdConstants =
+                $crate::check32::SimdConstants::new(
+                    Self::POLYNOMIAL as u32,
+                    <$ty>::BITS as u8,
+                    Self::PARAMS.refin,
+                );
+            /// The kernels take and return the complement of the crc register
+            const INITIAL_STATE: $ty = if Self::PARAMS.refin {
+                !(Self::PARAMS.init as $ty).reverse_bits()
+            } else {
+                !(Self::PARAMS.init as $ty)
+            };
+
+            #[doc = concat!("Creates a new `", stringify!($name), "` using naive approach")]
+            pub fn new_naive() -> Self {
+                Self {
+                    state: Self::INITIAL_STATE,
+                    compute: Self::compute_naive,
+                    backend: $crate::backend::Backend::Naive,
+                }
+            }
+
+            #[doc = concat!("Creates a new `", stringify!($name), "` using a table lookup approach")]
+            pub fn new_lookup() -> Self {
+                Self {
+                    state: Self::INITIAL_STATE,
+                    compute: Self::compute_lookup,
+                    backend: $crate::backend::Backend::Lookup,
+                }
+            }
+
+            #[cfg(feature = "hardware")]
+            #[doc = concat!("Creates a new `", stringify!($name), "` using the crc32 simd kernels on the polynomial")]
+            /// shifted into the top bits of the crc32 register.
+            /// - See [Backend](crate::backend::Backend) for the cpu features required by each
+            ///   simd backend
+            /// - Otherwise defaults to table lookup approach
+            pub fn new_simd() -> Self {
+                $crate::backend::Backend::SIMD
+                    .into_iter()
+                    .find_map(|backend| Self::new_with_backend(backend).ok())
+                    .unwrap_or_else(Self::new_lookup)
+            }
+
+            #[doc = concat!("Creates a new `", stringify!($name), "` using the given backend. Unlike the other")]
+            /// constructors this does not fall back to a slower backend, and fails if the backend
+            /// is not supported.
+            pub fn new_with_backend(
+                backend: $crate::backend::Backend,
+            ) -> Result<Self, $crate::backend::UnsupportedBackend> {
+                let compute = Self::select(backend)
+                    .ok_or($crate::backend::UnsupportedBackend(backend))?;
+                Ok(Self {
+                    state: Self::INITIAL_STATE,
+                    compute,
+                    backend,
+                })
+            }
+
+            #[doc = concat!("Returns the backends `", stringify!($name), "` supports on this cpu with the enabled")]
+            /// crate features
+            pub fn supported_backends() -> impl Iterator<Item = $crate::backend::Backend> {
+                $crate::backend::Backend::ALL
+                    .into_iter()
+                    .filter(|&backend| Self::select(backend).is_some())
+            }
+
+            /// Returns the backend this instance computes crcs with, after falling back from
+            /// unsupported cpu features
+            pub fn selected_backend(&self) -> $crate::backend::Backend {
+                self.backend
+            }
+
+            /// Resets the crc to its initial state, keeping the selected backend
+            pub fn reset(&mut self) {
+                self.state = Self::INITIAL_STATE;
+            }
+
+            #[cfg(feature = "parallel")]
+            /// Updates digest with data split across up to `threads` threads, each using the
+            /// approach this instance was created with. The partial crcs are merged using
+            /// [combine](Self::combine).
+            pub fn update_parallel(&mut self, data: &[u8], threads: usize) {
+                self.state = $crate::parallel::update_parallel(
+                    self.state,
+                    data,
+                    threads,
+                    Self::INITIAL_STATE,
+                    self.compute,
+                    |state_a, state_b, len_b| {
+                        let crc_a = Self::digest_of(state_a);
+                        let crc_b = Self::digest_of(state_b);
+                        Self::state_of(Self::combine(crc_a, crc_b, len_b))
+                    },
+                );
+            }
+
+            #[cfg(feature = "parallel")]
+            #[doc = concat!("Computes the `", stringify!($name), "` of data using up to `threads` threads, see")]
+            /// [update_parallel](Self::update_parallel).
+            /// - Uses the simd approach when the `hardware` feature is enabled
+            /// - Otherwise uses table lookup approach
+            pub fn checksum_parallel(data: &[u8], threads: usize) -> $ty {
+                let mut crc = <Self as Default>::default();
+                crc.update_parallel(data, threads);
+                $crate::crc::CrcDigest::digest(&crc)
+            }
+
+            /// Computes the crc of `A || B` given `crc_a`, `crc_b` and the length of `B` in bytes
+            pub fn combine(crc_a: $ty, crc_b: $ty, len_b: u64) -> $ty {
+                Self::combiner(len_b).combine(crc_a, crc_b)
+            }
+
+            /// Creates a [CrcCombiner](crate::crc::CrcCombiner) for combining crcs where the
+            /// second chunk is `len_b` bytes long
+            pub fn combiner(len_b: u64) -> $crate::crc::CrcCombiner<$ty> {
+                $crate::crc::CrcCombiner::new(Self::PARAMS, len_b)
+            }
+
+            /// Computes the crc of data after replacing `old` at `offset` with `new`, given
+            /// `old_crc`, the crc of the original `total_len` bytes, see
+            /// [Crc::patch](crate::crc::Crc::patch).
+            pub fn patch(
+                &self,
+                old_crc: $ty,
+                total_len: u64,
+                offset: u64,
+                old: &[u8],
+                new: &[u8],
+            ) -> $ty {
+                $crate::crc::patch(Self::PARAMS, self.compute, old_crc, total_len, offset, old, new)
+            }
+
+            /// Computes the crc of `data` using the table lookup approach, usable in const
+            /// contexts
+            pub const fn const_checksum(data: &[u8]) -> $ty {
+                let state = if Self::PARAMS.refin {
+                    <$crate::crc::Crc<$ty>>::const_lookup(
+                        Self::INITIAL_STATE,
+                        &Self::LOOKUP_TABLE[0],
+                        data,
+                    )
+                } else {
+                    <$crate::crc::Crc<$ty>>::const_msb_lookup(
+                        Self::INITIAL_STATE,
+                        &Self::LOOKUP_TABLE[0],
+                        data,
+                    )
+                };
+                Self::digest_of(state)
+            }
+
+            /// Converts the state used by the kernels into the digest
+            const fn digest_of(state: $ty) -> $ty {
+                let crc = !state;
+                let crc = if Self::PARAMS.refin != Self::PARAMS.refout {
+                    crc.reverse_bits()
+                } else {
+                    crc
+                };
+                crc ^ Self::PARAMS.xorout as $ty
+            }
+
+            /// Converts a digest into the state used by the kernels
+            const fn state_of(crc: $ty) -> $ty {
+                let crc = crc ^ Self::PARAMS.xorout as $ty;
+                let crc = if Self::PARAMS.refin != Self::PARAMS.refout {
+                    crc.reverse_bits()
+                } else {
+                    crc
+                };
+                !crc
+            }
+
+            fn select(backend: $crate::backend::Backend) -> Option<fn($ty, &[u8]) -> $ty> {
+                match backend {
+                    $crate::backend::Backend::Naive => Some(Self::compute_naive),
+                    $crate::backend::Backend::Lookup => Some(Self::compute_lookup),
+                    #[cfg(feature = "hardware")]
+                    _ => Self::select_simd(backend),
+                    #[cfg(not(feature = "hardware"))]
+                    _ => None,
+                }
+            }
+
+            #[cfg(feature = "hardware")]
+            fn select_simd(backend: $crate::backend::Backend) -> Option<fn($ty, &[u8]) -> $ty> {
+                use $crate::check32::SimdKernel;
+
+                // safety: the kernels are only selected when the cpu supports them
+                let compute: fn($ty, &[u8]) -> $ty =
+                    match SimdKernel::select(Self::PARAMS.refin, backend)? {
+                        #[cfg(any(
+                            target_arch = "x86",
+                            target_arch = "x86_64",
+                            target_arch = "aarch64"
+                        ))]
+                        SimdKernel::Reflected => |prev_crc, data| unsafe {
+                            SimdKernel::Reflected.update(
+                                prev_crc,
+                                &Self::SIMD_CONSTANTS,
+                                &Self::LOOKUP_TABLE,
+                                data,
+                            )
+                        },
+                        #[cfg(target_arch = "x86_64")]
+                        SimdKernel::ReflectedAvx2 => |prev_crc, data| unsafe {
+                            SimdKernel::ReflectedAvx2.update(
+                                prev_crc,
+                                &Self::SIMD_CONSTANTS,
+                                &Self::LOOKUP_TABLE,
+                                data,
+                            )
+                        },
+                        #[cfg(target_arch = "x86_64")]
+                        SimdKernel::ReflectedAvx512 => |prev_crc, data| unsafe {
+                            SimdKernel::ReflectedAvx512.update(
+                                prev_crc,
+                                &Self::SIMD_CONSTANTS,
+                                &Self::LOOKUP_TABLE,
+                                data,
+                            )
+                        },
+                        #[cfg(any(
+                            target_arch = "x86",
+                            target_arch = "x86_64",
+                            target_arch = "aarch64"
+                        ))]
+                        SimdKernel::Msb => |prev_crc, data| unsafe {
+                            SimdKernel::Msb.update(
+                                prev_crc,
+                                &Self::SIMD_CONSTANTS,
+                                &Self::LOOKUP_TABLE,
+                                data,
+                            )
+                        },
+                    };
+                Some(compute)
+            }
+
+            fn compute_naive(prev_crc: $ty, data: &[u8]) -> $ty {
+                if Self::PARAMS.refin {
+                    $crate::crc::Crc::<$ty>::naive(prev_crc, Self::POLYNOMIAL, data)
+                } else {
+                    $crate::crc::Crc::<$ty>::msb_naive(prev_crc, Self::POLYNOMIAL, data)
+                }
+            }
+
+            fn compute_lookup(prev_crc: $ty, data: &[u8]) -> $ty {
+                if Self::PARAMS.refin {
+                    $crate::crc::Crc::<$ty>::lookup(prev_crc, &Self::LOOKUP_TABLE, data)
+                } else {
+                    $crate::crc::Crc::<$ty>::msb_lookup(prev_crc, &Self::LOOKUP_TABLE, data)
+                }
+            }
+        }
+
+        impl $crate::crc::CrcDigest for $name {
+            type Output = $ty;
+
+            fn update(&mut self, data: &[u8]) {
+                self.state = (self.compute)(self.state, data);
+            }
+
+            fn digest(&self) -> $ty {
+                Self::digest_of(self.state)
+            }
+
+            fn update_zeros(&mut self, len: u64) {
+                let crc = Self::combiner(len).append_zeros(Self::digest_of(self.state));
+                self.state = Self::state_of(crc);
+            }
+        }
+
+        impl core::hash::Hasher for $name {
+            fn finish(&self) -> u64 {
+                $crate::crc::CrcDigest::digest(self) as u64
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                $crate::crc::CrcDigest::update(self, bytes);
+            }
+        }
+
+        impl Default for $name {
+            #[doc = concat!("Creates a new `", stringify!($name), "` using the simd approach when the `hardware`")]
+            /// feature is enabled and the table lookup approach otherwise
+            fn default() -> Self {
+                #[cfg(feature = "hardware")]
+                return Self::new_simd();
+                #[cfg(not(feature = "hardware"))]
+                return Self::new_lookup();
+            }
+        }
+    };
+}
+
+pub(crate) use fixed_crc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogue::{
+        CRC_16_IBM_SDLC, CRC_16_XMODEM, CRC_32_BZIP2, CRC_64_GO_ISO, CRC_8_MAXIM_DOW, CRC_8_SMBUS,
+    };
+
+    use crate::test_util::{assert_patch, assert_update_zeros, checksum, LARGE_DATA_2};
+
+    /// Runs a test generic over the width for crc parameters of every width, covering both bit
+    /// orders for the widths which share the crc32 simd kernels
+    macro_rules! for_each_params {
+        ($test:ident) => {
+            $test::<u8>(CRC_8_SMBUS);
+            $test::<u8>(CRC_8_MAXIM_DOW);
+            $test::<u16>(CRC_16_XMODEM);
+            $test::<u16>(CRC_16_IBM_SDLC);
+            $test::<u32>(CRC_32_BZIP2);
+            $test::<u64>(CRC_64_GO_ISO);
+        };
+    }
+
+    /// Checks every supported backend against the check value, and against the naive approach
+    /// for data updated in uneven chunks
+    fn test_params<W: CrcWidth>(params: CrcParams) {
+        let expected = checksum(Crc::<W>::new_naive_with_params(params), LARGE_DATA_2);
+        for backend in Crc::<W>::supported_backends(params) {
+            let crc = Crc::<W>::new_with_backend(params, backend).unwrap();
+            assert_eq!(crc.selected_backend(), backend);
+            assert_eq!(checksum(crc.clone(), b"123456789").into(), params.check);
+
+            let mut crc = crc;
+            crc.update(&LARGE_DATA_2[..7]);
+            crc.update(&LARGE_DATA_2[7..]);
+            assert_eq!(crc.digest(), expected);
+        }
+    }
+
+    #[test]
+    fn test_crc_params() {
+        for_each_params!(test_params);
+    }
+
+    #[test]
+    fn test_crc_reset() {
+        let mut crc = Crc::<u16>::new_lookup_with_params(CRC_16_IBM_SDLC);
+        crc.update(LARGE_DATA_2);
+        crc.reset();
+        crc.update(b"123456789");
+        assert_eq!(crc.digest(), 0x906E);
+    }
+
+    fn test_combine<W: CrcWidth>(params: CrcParams) {
+        let expected = checksum(Crc::<W>::new_naive_with_params(params), LARGE_DATA_2);
+        let crc = Crc::<W>::new_lookup_with_params(params);
+        for split in [0, 1, 9, 100, LARGE_DATA_2.len()] {
+            let (a, b) = LARGE_DATA_2.split_at(split);
+            let crc_a = checksum(crc.clone(), a);
+            let crc_b = checksum(crc.clone(), b);
+            assert_eq!(crc.combine(crc_a, crc_b, b.len() as u64), expected);
+        }
+    }
+
+    #[test]
+    fn test_crc_combine() {
+        for_each_params!(test_combine);
+
+        // combine stays usable from const fns, as it was before the widths shared CrcCombiner
+        const fn combine_u32(combiner: &CrcCombiner<u32>, crc_a: u32, crc_b: u32) -> u32 {
+            combiner.combine(crc_a, crc_b)
+        }
+        let crc = Crc::<u32>::new_lookup(0x1EDC6F41);
+        let (crc_a, crc_b) = (
+            checksum(crc.clone(), b"1234"),
+            checksum(crc.clone(), b"56789"),
+        );
+        assert_eq!(combine_u32(&crc.combiner(5), crc_a, crc_b), 0xE3069283);
+    }
+
+    fn test_update_zeros<W: CrcWidth>(params: CrcParams) {
+        for backend in Crc::<W>::supported_backends(params) {
+            assert_update_zeros(Crc::<W>::new_with_backend(params, backend).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_crc_update_zeros() {
+        for_each_params!(test_update_zeros);
+    }
+
+    fn test_patch<W: CrcWidth>(params: CrcParams) {
+        for backend in Crc::<W>::supported_backends(params) {
+            let crc = Crc::<W>::new_with_backend(params, backend).unwrap();
+            assert_patch(&crc, |old_crc, total_len, offset, old, new| {
+                crc.patch(old_crc, total_len, offset, old, new)
+            });
+        }
+    }
+
+    #[test]
+    fn test_crc_patch() {
+        for_each_params!(test_patch);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_crc_patch_out_of_bounds() {
+        let crc = Crc::<u32>::new_lookup_with_params(CRC_32_BZIP2);
+        crc.patch(0, 10, 8, b"abc", b"def");
+    }
+
+    #[test]
+    fn test_default_update_zeros() {
+        struct Bytes(Vec<u8>);
+
+        impl CrcDigest for Bytes {
+            type Output = usize;
+
+            fn update(&mut self, data: &[u8]) {
+                self.0.extend_from_slice(data);
+            }
+
+            fn digest(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        let mut bytes = Bytes(Vec::new());
+        bytes.update_zeros(10_000);
+        assert_eq!(bytes.digest(), 10_000);
+        assert!(bytes.0.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_multiply_mod() {
+        let polynomial = 0x42F0E1EBA9EA3693;
+        let x = |n| Crc::<u64>::xn_mod(n, polynomial);
+        assert_eq!(x(63), 1 << 63);
+        assert_eq!(x(64), polynomial);
+        assert_eq!(Crc::<u64>::multiply_mod(x(100), x(200), polynomial), x(300));
+        assert_eq!(Crc::<u8>::multiply_mod(0x80, 0x02, 0x07), 0x07);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_crc_width_mismatch() {
+        Crc::<u16>::new_naive_with_params(CRC_8_SMBUS);
+    }
+}
@@ -0,0 +1,29 @@
+/// Detects x86 or x86_64 cpu features at runtime when the `std` feature is enabled. Otherwise
+/// the features are detected at compile time from the enabled target features, e.g. by building
+/// with `-C target-feature=+sse4.2,+pclmulqdq`. Several features are checked in a single call, so
+/// that the compile time check stays a single `cfg!` expression.
+#[cfg(all(feature = "hardware", any(target_arch = "x86", target_arch = "x86_64")))]
+macro_rules! x86_feature_detected {
+    ($feature:tt $(, $rest:tt)*) => {{
+        #[cfg(feature = "std")]
+        let detected = std::is_x86_feature_detected!($feature) $(&& std::is_x86_feature_detected!($rest))*;
+        #[cfg(not(feature = "std"))]
+        let detected = cfg!(all(target_feature = $feature $(, target_feature = $rest)*));
+        detected
+    }};
+}
+
+/// Detects aarch64 cpu features at runtime when the `std` feature is enabled. Otherwise the
+/// features are detected at compile time from the enabled target features, e.g. by building with
+/// `-C target-feature=+crc,+aes`. Several features are checked in a single call, like
+/// `x86_feature_detected!`.
+#[cfg(all(feature = "hardware", target_arch = "aarch64"))]
+macro_rules! aarch64_feature_detected {
+    ($feature:tt $(, $rest:tt)*) => {{
+        #[cfg(feature = "std")]
+        let detected = std::arch::is_aarch64_feature_detected!($feature) $(&& std::arch::is_aarch64_feature_detected!($rest))*;
+        #[cfg(not(feature = "std"))]
+        let detected = cfg!(all(target_feature = $feature $(, target_feature = $rest)*));
+        detected
+    }};
+}
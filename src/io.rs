@@ -0,0 +1,720 @@
+//! Adapters computing a crc over data passing through a reader or writer
+//!
+//! Every crc type implements [std::io::Write], so a crc can be used directly as the destination of
+//! [std::io::copy]. [CrcReader] and [CrcWriter] wrap an existing reader or writer and update any
+//! [CrcDigest] with every byte that passes through, for when the data is needed as well.
+//! [VerifyingReader] additionally checks the crc against an expected value at the end of the
+//! stream. [update_sparse] checksums a sparse file without reading its holes.
+//!
+//! ```
+//! use librscrc::prelude::*;
+//! use std::io::{self, Read};
+//!
+//! let mut crc = Crc32C::new_simd();
+//! io::copy(&mut &b"123456789"[..], &mut crc).unwrap();
+//! assert_eq!(crc.digest(), 0xE3069283);
+//!
+//! let mut reader = CrcReader::new(&b"123456789"[..], Crc64ECMA::new_simd());
+//! let mut data = Vec::new();
+//! reader.read_to_end(&mut data).unwrap();
+//! assert_eq!(data, b"123456789");
+//! assert_eq!(reader.crc().digest(), 0x995DC9BBDF1939FA);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+use crate::check16::{Crc16CcittFalse, Crc16Kermit, Crc16Modbus, Crc16X25, Crc16Xmodem};
+use crate::check32::{Crc32, Crc32C};
+use crate::check64::{Crc64ECMA, Crc64ISO};
+use crate::check8::{Crc8Autosar, Crc8Maxim, Crc8Smbus};
+use crate::crc::{Crc, CrcDigest, CrcWidth};
+
+/// Reader adapter which updates a crc with all the data read from the inner reader
+#[derive(Debug)]
+pub struct CrcReader<R, D> {
+    inner: R,
+    crc: D,
+}
+
+impl<R, D> CrcReader<R, D> {
+    /// Wraps `inner`, updating `crc` with the data read from it
+    pub fn new(inner: R, crc: D) -> Self {
+        Self { inner, crc }
+    }
+
+    /// Returns the crc of the data read so far
+    pub fn crc(&self) -> &D {
+        &self.crc
+    }
+
+    /// Returns the crc of the data read so far mutably
+    pub fn crc_mut(&mut self) -> &mut D {
+        &mut self.crc
+    }
+
+    /// Returns a reference to the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner reader. Data read directly from it is not included
+    /// in the crc.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Consumes the adapter, returning the inner reader and the crc
+    pub fn into_parts(self) -> (R, D) {
+        (self.inner, self.crc)
+    }
+}
+
+impl<R: Read, D: CrcDigest> Read for CrcReader<R, D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.crc.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Writer adapter which updates a crc with all the data written to the inner writer
+#[derive(Debug)]
+pub struct CrcWriter<W, D> {
+    inner: W,
+    crc: D,
+}
+
+impl<W, D> CrcWriter<W, D> {
+    /// Wraps `inner`, updating `crc` with the data written to it
+    pub fn new(inner: W, crc: D) -> Self {
+        Self { inner, crc }
+    }
+
+    /// Returns the crc of the data written so far
+    pub fn crc(&self) -> &D {
+        &self.crc
+    }
+
+    /// Returns the crc of the data written so far mutably
+    pub fn crc_mut(&mut self) -> &mut D {
+        &mut self.crc
+    }
+
+    /// Returns a reference to the inner writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer. Data written directly to it is not
+    /// included in the crc.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Consumes the adapter, returning the inner writer and the crc
+    pub fn into_parts(self) -> (W, D) {
+        (self.inner, self.crc)
+    }
+}
+
+impl<W: Write, D: CrcDigest> Write for CrcWriter<W, D> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // only the bytes accepted by the inner writer are part of the crc
+        let written = self.inner.write(buf)?;
+        self.crc.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Byte order of a crc stored in a stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Error wrapped in the [io::Error] of kind [ErrorKind::InvalidData] returned by
+/// [VerifyingReader] when the crc of the stream does not match the expected value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcMismatch {
+    /// The expected crc
+    pub expected: u64,
+    /// The crc computed over the stream
+    pub actual: u64,
+}
+
+impl fmt::Display for CrcMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "crc mismatch, expected {:#x} but computed {:#x}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl Error for CrcMismatch {}
+
+#[derive(Debug, Clone, Copy)]
+enum Expected {
+    Value(u64),
+    Trailer(Endian),
+}
+
+/// Reader adapter which verifies the crc of the data read from the inner reader.
+///
+/// The expected crc is either known up front or stored in the last 4 or 8 bytes of the stream. In
+/// the latter case the trailing crc is not returned to the caller and not included in the crc.
+/// Once the inner reader reaches end of file, the crc is compared with the expected value and an
+/// [io::Error] of kind [ErrorKind::InvalidData] wrapping a [CrcMismatch] is returned if they
+/// differ.
+///
+/// ```
+/// use librscrc::io::Endian;
+/// use librscrc::prelude::*;
+/// use std::io::{ErrorKind, Read};
+///
+/// let mut reader = VerifyingReader::with_expected_crc32(&b"123456789"[..], Crc32C::new_simd(), 0xE3069283);
+/// let mut data = Vec::new();
+/// assert!(reader.read_to_end(&mut data).is_ok());
+///
+/// let framed = b"123456789\xE3\x06\x92\x84";
+/// let mut reader = VerifyingReader::with_trailing_crc32(&framed[..], Crc32C::new_simd(), Endian::Big);
+/// let mut data = Vec::new();
+/// assert_eq!(reader.read_to_end(&mut data).unwrap_err().kind(), ErrorKind::InvalidData);
+/// assert_eq!(data, b"123456789");
+/// ```
+pub struct VerifyingReader<R, D> {
+    inner: R,
+    crc: D,
+    update: fn(&mut D, &[u8]),
+    digest: fn(&D) -> u64,
+    width: usize,
+    expected: Expected,
+    // bytes held back because they may belong to the trailing crc
+    tail: [u8; 8],
+    tail_len: usize,
+}
+
+impl<R, D: CrcDigest<Output = u32>> VerifyingReader<R, D> {
+    /// Wraps `inner`, failing at end of file unless the crc32 of the data is `expected`
+    pub fn with_expected_crc32(inner: R, crc: D, expected: u32) -> Self {
+        Self::new_crc32(inner, crc, Expected::Value(expected as u64))
+    }
+
+    /// Wraps `inner`, failing at end of file unless the last 4 bytes of the stream hold the crc32
+    /// of the data before them in the given byte order
+    pub fn with_trailing_crc32(inner: R, crc: D, endian: Endian) -> Self {
+        Self::new_crc32(inner, crc, Expected::Trailer(endian))
+    }
+
+    fn new_crc32(inner: R, crc: D, expected: Expected) -> Self {
+        Self::new(
+            inner,
+            crc,
+            D::update,
+            |crc| crc.digest() as u64,
+            4,
+            expected,
+        )
+    }
+}
+
+impl<R, D: CrcDigest<Output = u64>> VerifyingReader<R, D> {
+    /// Wraps `inner`, failing at end of file unless the crc64 of the data is `expected`
+    pub fn with_expected_crc64(inner: R, crc: D, expected: u64) -> Self {
+        Self::new_crc64(inner, crc, Expected::Value(expected))
+    }
+
+    /// Wraps `inner`, failing at end of file unless the last 8 bytes of the stream hold the crc64
+    /// of the data before them in the given byte order
+    pub fn with_trailing_crc64(inner: R, crc: D, endian: Endian) -> Self {
+        Self::new_crc64(inner, crc, Expected::Trailer(endian))
+    }
+
+    fn new_crc64(inner: R, crc: D, expected: Expected) -> Self {
+        Self::new(inner, crc, D::update, D::digest, 8, expected)
+    }
+}
+
+impl<R, D> VerifyingReader<R, D> {
+    fn new(
+        inner: R,
+        crc: D,
+        update: fn(&mut D, &[u8]),
+        digest: fn(&D) -> u64,
+        width: usize,
+        expected: Expected,
+    ) -> Self {
+        Self {
+            inner,
+            crc,
+            update,
+            digest,
+            width,
+            expected,
+            tail: [0; 8],
+            tail_len: 0,
+        }
+    }
+
+    /// Returns the crc of the data read so far
+    pub fn crc(&self) -> &D {
+        &self.crc
+    }
+
+    /// Returns a reference to the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consumes the adapter, returning the inner reader and the crc
+    pub fn into_parts(self) -> (R, D) {
+        (self.inner, self.crc)
+    }
+
+    fn verify(&self) -> io::Result<()> {
+        let expected = match self.expected {
+            Expected::Value(expected) => expected,
+            Expected::Trailer(_) if self.tail_len < self.width => {
+                return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "stream is shorter than its trailing crc",
+                ));
+            }
+            Expected::Trailer(Endian::Little) => self.tail[..self.width]
+                .iter()
+                .rev()
+                .fold(0, |value, &byte| value << 8 | byte as u64),
+            Expected::Trailer(Endian::Big) => self.tail[..self.width]
+                .iter()
+                .fold(0, |value, &byte| value << 8 | byte as u64),
+        };
+        let actual = (self.digest)(&self.crc);
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                ErrorKind::InvalidData,
+                CrcMismatch { expected, actual },
+            ))
+        }
+    }
+
+    /// Moves the bytes which are no longer part of the trailer to the front of `buf`, where the
+    /// first `read` bytes of `buf` follow the held back bytes in the stream. Returns the number of
+    /// bytes released to the caller.
+    fn hold_back_trailer(&mut self, buf: &mut [u8], read: usize) -> usize {
+        let width = self.width;
+        let total = self.tail_len + read;
+        if total <= width {
+            self.tail[self.tail_len..total].copy_from_slice(&buf[..read]);
+            self.tail_len = total;
+            return 0;
+        }
+
+        let released = total - width;
+        let mut tail = [0; 8];
+        if read >= width {
+            tail[..width].copy_from_slice(&buf[read - width..read]);
+        } else {
+            let kept = width - read;
+            tail[..kept].copy_from_slice(&self.tail[self.tail_len - kept..self.tail_len]);
+            tail[kept..width].copy_from_slice(&buf[..read]);
+        }
+
+        if released >= self.tail_len {
+            buf.copy_within(..released - self.tail_len, self.tail_len);
+            buf[..self.tail_len].copy_from_slice(&self.tail[..self.tail_len]);
+        } else {
+            buf[..released].copy_from_slice(&self.tail[..released]);
+        }
+        self.tail = tail;
+        self.tail_len = width;
+        released
+    }
+}
+
+impl<R: Read, D> Read for VerifyingReader<R, D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let read = self.inner.read(buf)?;
+            if read == 0 {
+                self.verify()?;
+                return Ok(0);
+            }
+            let released = match self.expected {
+                Expected::Value(_) => read,
+                Expected::Trailer(_) => self.hold_back_trailer(buf, read),
+            };
+            // returning 0 would signal end of file while only trailer candidates were read
+            if released > 0 {
+                (self.update)(&mut self.crc, &buf[..released]);
+                return Ok(released);
+            }
+        }
+    }
+}
+
+/// Updates `crc` with a sparse file of `len` bytes given the ranges of the file holding data, e.g.
+/// as found with `lseek` and `SEEK_DATA`/`SEEK_HOLE`. The data ranges are read from `reader`,
+/// while the holes in between are zeros and are added with [CrcDigest::update_zeros] without
+/// reading them.
+///
+/// The ranges must be sorted, non overlapping and end within `len`, otherwise an [io::Error] of
+/// kind [ErrorKind::InvalidInput] is returned. A reader ending within a data range results in an
+/// error of kind [ErrorKind::UnexpectedEof]. The crc is left partially updated on error.
+///
+/// ```
+/// use librscrc::io::update_sparse;
+/// use librscrc::prelude::*;
+/// use std::io::Cursor;
+///
+/// let mut file = vec![0u8; 1 << 20];
+/// file[4096..4105].copy_from_slice(b"123456789");
+///
+/// let mut crc = Crc32C::new_simd();
+/// update_sparse(&mut crc, &mut Cursor::new(&file), [4096..8192], file.len() as u64).unwrap();
+///
+/// let mut expected = Crc32C::new_simd();
+/// expected.update(&file);
+/// assert_eq!(crc.digest(), expected.digest());
+/// ```
+pub fn update_sparse<R, D, I>(
+    crc: &mut D,
+    reader: &mut R,
+    data_ranges: I,
+    len: u64,
+) -> io::Result<()>
+where
+    R: Read + Seek,
+    D: CrcDigest,
+    I: IntoIterator<Item = Range<u64>>,
+{
+    let mut position = 0;
+    let mut buf = [0u8; 8192];
+    for range in data_ranges {
+        if range.start < position || range.end < range.start || range.end > len {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "data ranges must be sorted, non overlapping and within the file",
+            ));
+        }
+        crc.update_zeros(range.start - position);
+        reader.seek(SeekFrom::Start(range.start))?;
+        let mut remaining = range.end - range.start;
+        while remaining > 0 {
+            let chunk = remaining.min(buf.len() as u64) as usize;
+            reader.read_exact(&mut buf[..chunk])?;
+            crc.update(&buf[..chunk]);
+            remaining -= chunk as u64;
+        }
+        position = range.end;
+    }
+    crc.update_zeros(len - position);
+    Ok(())
+}
+
+// lets the crc types be the destination of io::copy, the adapters only require CrcDigest
+macro_rules! impl_write {
+    (impl<$($generic:ident: $bound:path),*> for $ty:ty) => {
+        impl<$($generic: $bound),*> Write for $ty {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                CrcDigest::update(self, buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+    };
+    ($ty:ty) => {
+        impl_write!(impl<> for $ty);
+    };
+}
+
+impl_write!(Crc8Smbus);
+impl_write!(Crc8Maxim);
+impl_write!(Crc8Autosar);
+impl_write!(Crc16Modbus);
+impl_write!(Crc16CcittFalse);
+impl_write!(Crc16Xmodem);
+impl_write!(Crc16Kermit);
+impl_write!(Crc16X25);
+impl_write!(Crc32);
+impl_write!(Crc32C);
+impl_write!(Crc64ECMA);
+impl_write!(Crc64ISO);
+impl_write!(impl<W: CrcWidth> for Crc<W>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check32::CustomCrc32;
+    use crate::rolling::RollingCrc32;
+
+    const DATA: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    /// Reader which returns at most 3 bytes per call
+    struct Trickle<'a>(&'a [u8]);
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(3).min(self.0.len());
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    fn write_digest<D: Write>(mut crc: D, data: &[u8]) -> D {
+        crc.write_all(data).unwrap();
+        crc
+    }
+
+    #[test]
+    fn test_copy_into_crc() {
+        let mut crc32 = Crc32::new_lookup();
+        io::copy(&mut Trickle(DATA), &mut crc32).unwrap();
+        assert_eq!(crc32.digest(), 0x414FA339);
+
+        let mut crc32c = Crc32C::new_lookup();
+        io::copy(&mut Trickle(DATA), &mut crc32c).unwrap();
+        assert_eq!(crc32c.digest(), 0x22620404);
+
+        let mut custom = CustomCrc32::new_lookup(0x1EDC6F41);
+        io::copy(&mut Trickle(DATA), &mut custom).unwrap();
+        assert_eq!(custom.digest(), 0x22620404);
+
+        let mut crc64 = Crc64ECMA::new_lookup();
+        io::copy(&mut Trickle(DATA), &mut crc64).unwrap();
+        assert_eq!(
+            crc64.digest(),
+            write_digest(Crc64ECMA::new_lookup(), DATA).digest()
+        );
+
+        let mut crc64 = Crc64ISO::new_lookup();
+        io::copy(&mut Trickle(DATA), &mut crc64).unwrap();
+        assert_eq!(
+            crc64.digest(),
+            write_digest(Crc64ISO::new_lookup(), DATA).digest()
+        );
+    }
+
+    #[test]
+    fn test_crc_reader() {
+        let mut reader = CrcReader::new(Trickle(DATA), Crc32C::new_lookup());
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, DATA);
+        assert_eq!(reader.crc().digest(), 0x22620404);
+
+        let (_, crc) = reader.into_parts();
+        assert_eq!(crc.digest(), 0x22620404);
+    }
+
+    #[test]
+    fn test_crc_writer() {
+        let mut writer = CrcWriter::new(Vec::new(), Crc64ISO::new_lookup());
+        for chunk in DATA.chunks(5) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.flush().unwrap();
+        let (data, crc) = writer.into_parts();
+        assert_eq!(data, DATA);
+        assert_eq!(
+            crc.digest(),
+            write_digest(Crc64ISO::new_lookup(), DATA).digest()
+        );
+    }
+
+    #[test]
+    fn test_crc_writer_rolling() {
+        // the adapters accept any CrcDigest, including ones which do not implement io::Write
+        let mut writer = CrcWriter::new(Vec::new(), RollingCrc32::new(0x1EDC6F41, 4));
+        writer.write_all(DATA).unwrap();
+        assert_eq!(writer.crc().digest(), 0x22620404);
+
+        let mut reader = CrcReader::new(Trickle(DATA), RollingCrc32::new(0x1EDC6F41, 4));
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        assert_eq!(reader.crc().digest(), 0x22620404);
+    }
+
+    #[test]
+    fn test_crc_writer_short_write() {
+        // the inner writer only accepts 4 bytes so the crc must only cover those
+        let mut buf = [0u8; 4];
+        let mut writer = CrcWriter::new(&mut buf[..], Crc32::new_lookup());
+        assert_eq!(writer.write(DATA).unwrap(), 4);
+        assert_eq!(
+            writer.crc().digest(),
+            write_digest(Crc32::new_lookup(), &DATA[..4]).digest()
+        );
+    }
+
+    fn read_verified<R: Read>(mut reader: R) -> (Vec<u8>, io::Result<usize>) {
+        let mut data = Vec::new();
+        let result = reader.read_to_end(&mut data);
+        (data, result)
+    }
+
+    fn crc_mismatch(result: io::Result<usize>) -> CrcMismatch {
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        *error
+            .into_inner()
+            .unwrap()
+            .downcast::<CrcMismatch>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verifying_reader_expected() {
+        let reader =
+            VerifyingReader::with_expected_crc32(Trickle(DATA), Crc32::new_lookup(), 0x414FA339);
+        let (data, result) = read_verified(reader);
+        assert_eq!(data, DATA);
+        assert_eq!(result.unwrap(), DATA.len());
+
+        let reader = VerifyingReader::with_expected_crc32(DATA, Crc32C::new_lookup(), 0x414FA339);
+        let (data, result) = read_verified(reader);
+        assert_eq!(data, DATA);
+        assert_eq!(
+            crc_mismatch(result),
+            CrcMismatch {
+                expected: 0x414FA339,
+                actual: 0x22620404
+            }
+        );
+
+        let crc64 = write_digest(Crc64ECMA::new_lookup(), DATA).digest();
+        let reader =
+            VerifyingReader::with_expected_crc64(Trickle(DATA), Crc64ECMA::new_lookup(), crc64);
+        assert!(read_verified(reader).1.is_ok());
+        let reader = VerifyingReader::with_expected_crc64(DATA, Crc64ISO::new_lookup(), crc64);
+        assert_eq!(crc_mismatch(read_verified(reader).1).expected, crc64);
+    }
+
+    #[test]
+    fn test_verifying_reader_trailer() {
+        let crc32 = write_digest(Crc32C::new_lookup(), DATA).digest();
+        let crc64 = write_digest(Crc64ISO::new_lookup(), DATA).digest();
+        let framed = |crc: &[u8]| [DATA, crc].concat();
+
+        for endian in [Endian::Little, Endian::Big] {
+            let (crc32_bytes, crc64_bytes) = match endian {
+                Endian::Little => (crc32.to_le_bytes(), crc64.to_le_bytes()),
+                Endian::Big => (crc32.to_be_bytes(), crc64.to_be_bytes()),
+            };
+
+            let stream = framed(&crc32_bytes);
+            let reader = VerifyingReader::with_trailing_crc32(
+                Trickle(&stream),
+                Crc32C::new_lookup(),
+                endian,
+            );
+            let (data, result) = read_verified(reader);
+            assert_eq!(data, DATA);
+            assert!(result.is_ok());
+
+            let reader =
+                VerifyingReader::with_trailing_crc32(&stream[..], Crc32::new_lookup(), endian);
+            let (data, result) = read_verified(reader);
+            assert_eq!(data, DATA);
+            assert_eq!(crc_mismatch(result).expected, crc32 as u64);
+
+            let stream = framed(&crc64_bytes);
+            let reader = VerifyingReader::with_trailing_crc64(
+                Trickle(&stream),
+                Crc64ISO::new_lookup(),
+                endian,
+            );
+            let (data, result) = read_verified(reader);
+            assert_eq!(data, DATA);
+            assert!(result.is_ok());
+
+            let reader =
+                VerifyingReader::with_trailing_crc64(&stream[..], Crc64ECMA::new_lookup(), endian);
+            let (data, result) = read_verified(reader);
+            assert_eq!(data, DATA);
+            assert_eq!(crc_mismatch(result).expected, crc64);
+        }
+    }
+
+    #[test]
+    fn test_verifying_reader_trailer_only() {
+        let empty_crc = Crc32C::new_lookup().digest().to_le_bytes();
+        let reader = VerifyingReader::with_trailing_crc32(
+            &empty_crc[..],
+            Crc32C::new_lookup(),
+            Endian::Little,
+        );
+        let (data, result) = read_verified(reader);
+        assert!(data.is_empty());
+        assert!(result.is_ok());
+
+        let reader =
+            VerifyingReader::with_trailing_crc64(&DATA[..7], Crc64ECMA::new_lookup(), Endian::Big);
+        let (data, result) = read_verified(reader);
+        assert!(data.is_empty());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_update_sparse() {
+        let mut file = vec![0u8; 100_000];
+        file[10..10 + DATA.len()].copy_from_slice(DATA);
+        file[50_000..50_000 + DATA.len()].copy_from_slice(DATA);
+        file[99_990..].copy_from_slice(&DATA[..10]);
+        let expected = write_digest(Crc64ECMA::new_lookup(), &file).digest();
+
+        let ranges = [
+            vec![],
+            vec![0..100_000],
+            vec![10..60, 50_000..50_100, 99_990..100_000],
+            vec![0..20_000, 20_000..20_000, 40_000..60_000, 99_000..100_000],
+        ];
+        for data_ranges in ranges {
+            let mut crc = Crc64ECMA::new_lookup();
+            let mut reader = io::Cursor::new(&file);
+            update_sparse(&mut crc, &mut reader, data_ranges.clone(), 100_000).unwrap();
+            if data_ranges.is_empty() {
+                assert_eq!(
+                    crc.digest(),
+                    write_digest(Crc64ECMA::new_lookup(), &[0; 100_000]).digest()
+                );
+            } else {
+                assert_eq!(crc.digest(), expected);
+            }
+        }
+
+        let mut reader = io::Cursor::new(&file);
+        #[allow(clippy::reversed_empty_ranges)]
+        let invalid_ranges = [vec![20..30, 10..15], vec![10..5], vec![0..100_001]];
+        for data_ranges in invalid_ranges {
+            let err = update_sparse(&mut Crc32C::new_lookup(), &mut reader, data_ranges, 100_000)
+                .unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        }
+        let err = update_sparse(
+            &mut Crc32C::new_lookup(),
+            &mut reader,
+            [0..200_000],
+            200_000,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
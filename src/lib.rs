@@ -1,6 +1,11 @@
 //! # librscrc
 //!
-//! `librscrc` is a collection of crc32 algorithms with support for various approaches and custom polynomial.
+//! `librscrc` is a collection of crc32 and crc64 algorithms with support for various approaches and custom polynomial.
+//!
+//! The crate is `no_std` when the default `std` feature is disabled. Cpu features are then
+//! detected at compile time through `cfg(target_feature)`, so the simd and hardware approaches
+//! require building with e.g. `-C target-feature=+sse4.2,+pclmulqdq`, and otherwise fall back to
+//! the table lookup approach.
 //!
 //! # Usage examples
 //! ## naive
@@ -42,8 +47,73 @@
 //! crc.update(b"123456789");
 //! assert_eq!(crc.digest(), 0xCBF43926);
 //!
+//! // crc64 polynomials are provided without the implicit x^64 term.
+//! let mut crc = CustomCrc64::new_simd(0xAD93D23594C93659);
+//! crc.update(b"123456789");
+//! assert_eq!(crc.digest(), 0xAE8B14860A799888);
 //!```
+//!
+//! # Crc parameters example
+//! ```
+//! use librscrc::prelude::*;
+//!
+//! // CRC-32/BZIP2 is not reflected and uses a different init and xorout.
+//! let params = CrcParams {
+//!     width: 32,
+//!     poly: 0x04C11DB7,
+//!     init: 0xFFFFFFFF,
+//!     refin: false,
+//!     refout: false,
+//!     xorout: 0xFFFFFFFF,
+//!     check: 0xFC891918,
+//!     residue: 0xC704DD7B,
+//! };
+//! let mut crc = CustomCrc32::with_params(params);
+//! crc.update(b"123456789");
+//! assert_eq!(crc.digest(), 0xFC891918);
+//! ```
+//!
+//! # Backend example
+//! ```
+//! use librscrc::prelude::*;
+//!
+//! // constructors like new_simd fall back to slower backends on older cpus
+//! let crc = Crc32C::new_simd();
+//! assert!(Crc32C::supported_backends().any(|backend| backend == crc.selected_backend()));
+//!
+//! // new_with_backend fails instead of falling back
+//! let mut crc = Crc32C::new_with_backend(Backend::Lookup).unwrap();
+//! crc.update(b"123456789");
+//! assert_eq!(crc.digest(), 0xE3069283);
+//! assert_eq!(
+//!     Crc64ECMA::new_with_backend(Backend::Hardware).err(),
+//!     Some(UnsupportedBackend(Backend::Hardware))
+//! );
+//! ```
+//!
+//! # Combine example
+//! ```
+//! use librscrc::prelude::*;
+//!
+//! let mut crc_a = Crc32C::new_simd();
+//! crc_a.update(b"1234");
+//! let mut crc_b = Crc32C::new_simd();
+//! crc_b.update(b"56789");
+//! assert_eq!(Crc32C::combine(crc_a.digest(), crc_b.digest(), 5), 0xE3069283);
+//! ```
+//!
+//! # Const example
+//! ```
+//! use librscrc::prelude::*;
+//!
+//! const HEADER_CRC: u32 = Crc32C::const_checksum(b"123456789");
+//! assert_eq!(HEADER_CRC, 0xE3069283);
+//!
+//! const HEADER_CRC64: u64 = Crc64ECMA::const_checksum(b"123456789");
+//! assert_eq!(HEADER_CRC64, 0x995DC9BBDF1939FA);
+//! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(
     all(
         feature = "nightly",
@@ -53,6 +123,27 @@
     feature(stdsimd)
 )]
 
+#[macro_use]
+mod detect;
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub mod async_io;
+pub mod backend;
+pub mod catalogue;
+pub mod check16;
 pub mod check32;
+pub mod check64;
+pub mod check8;
+pub mod crc;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod params;
 pub mod prelude;
-mod check64;
+pub mod rolling;
+
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "digest")]
+mod rustcrypto;
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,108 @@
+use std::thread;
+
+/// Minimum number of bytes processed by a single thread, smaller inputs are not worth a thread
+const MIN_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Splits data into at most `threads` chunks and computes them in parallel.
+/// - The first chunk continues from `state` on the calling thread
+/// - Every other chunk is computed on its own thread starting from `empty`
+/// - The partial results are merged in order using `combine`, which is given the length of the
+///   second chunk
+pub(crate) fn update_parallel<T, C, M>(
+    state: T,
+    data: &[u8],
+    threads: usize,
+    empty: T,
+    compute: C,
+    combine: M,
+) -> T
+where
+    T: Copy + Send,
+    C: Fn(T, &[u8]) -> T + Sync,
+    M: Fn(T, T, u64) -> T,
+{
+    let chunk_size = data.len().div_ceil(threads.max(1)).max(MIN_CHUNK_SIZE);
+    let mut chunks = data.chunks(chunk_size);
+    let first = match chunks.next() {
+        Some(first) => first,
+        None => return state,
+    };
+    let compute = &compute;
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .map(|chunk| (chunk.len(), scope.spawn(move || compute(empty, chunk))))
+            .collect();
+
+        let mut state = compute(state, first);
+        for (len, handle) in handles {
+            state = combine(state, handle.join().unwrap(), len as u64);
+        }
+        state
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::test_util::pseudo_random_data;
+
+    const CRC32_BZIP2: CrcParams = CrcParams {
+        width: 32,
+        poly: 0x04C11DB7,
+        init: 0xFFFFFFFF,
+        refin: false,
+        refout: false,
+        xorout: 0xFFFFFFFF,
+        check: 0xFC891918,
+        residue: 0xC704DD7B,
+    };
+
+    #[test]
+    fn test_crc32_parallel() {
+        let data = pseudo_random_data(1_000_003);
+        for threads in [0, 1, 2, 3, 8] {
+            let mut expected = Crc32::new_lookup();
+            expected.update(&data);
+            assert_eq!(Crc32::checksum_parallel(&data, threads), expected.digest());
+
+            let mut expected = Crc32C::new_lookup();
+            expected.update(&data);
+            assert_eq!(Crc32C::checksum_parallel(&data, threads), expected.digest());
+
+            let mut expected = CustomCrc32::new_lookup_with_params(CRC32_BZIP2);
+            expected.update(&data);
+            let mut crc = CustomCrc32::with_params(CRC32_BZIP2);
+            crc.update(&data[..5]);
+            crc.update_parallel(&data[5..], threads);
+            assert_eq!(crc.digest(), expected.digest());
+        }
+    }
+
+    #[test]
+    fn test_crc64_parallel() {
+        let data = pseudo_random_data(1_000_003);
+        for threads in [0, 1, 2, 3, 8] {
+            let mut expected = Crc64ECMA::new_lookup();
+            expected.update(&data);
+            assert_eq!(
+                Crc64ECMA::checksum_parallel(&data, threads),
+                expected.digest()
+            );
+
+            let mut expected = Crc64ISO::new_lookup();
+            expected.update(&data);
+            assert_eq!(
+                Crc64ISO::checksum_parallel(&data, threads),
+                expected.digest()
+            );
+
+            let mut expected = CustomCrc64::new_lookup(0xAD93D23594C93659);
+            expected.update(&data);
+            let mut crc = CustomCrc64::new_lookup(0xAD93D23594C93659);
+            crc.update(&data[..5]);
+            crc.update_parallel(&data[5..], threads);
+            assert_eq!(crc.digest(), expected.digest());
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/// Crc parameters following the Rocksoft™ model described in
+/// [A painless guide to crc error detection algorithms](http://www.ross.net/crc/download/crc_v3.txt)
+/// and used by the [reveng catalogue](https://reveng.sourceforge.io/crc-catalogue/).
+///
+/// `poly` and `init` are given in their normal (msb first) form, irrespective of `refin`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcParams {
+    /// Width of the crc in bits
+    pub width: u8,
+    /// Generator polynomial without the implicit x^width term
+    pub poly: u64,
+    /// Initial value of the crc register
+    pub init: u64,
+    /// Whether the input bytes are reflected i.e. processed lsb first
+    pub refin: bool,
+    /// Whether the final register value is reflected before applying `xorout`
+    pub refout: bool,
+    /// Value xored into the final register value
+    pub xorout: u64,
+    /// Crc of the ascii string "123456789"
+    pub check: u64,
+    /// Final register value, without `xorout`, of any codeword
+    pub residue: u64,
+}
@@ -1,7 +1,41 @@
+pub use crate::backend::Backend;
+pub use crate::backend::UnsupportedBackend;
+
+pub use crate::check8::Crc8Autosar;
+pub use crate::check8::Crc8Maxim;
+pub use crate::check8::Crc8Smbus;
+pub use crate::check8::CustomCrc8;
+
+pub use crate::check16::Crc16CcittFalse;
+pub use crate::check16::Crc16IbmSdlc;
+pub use crate::check16::Crc16Kermit;
+pub use crate::check16::Crc16Modbus;
+pub use crate::check16::Crc16X25;
+pub use crate::check16::Crc16Xmodem;
+pub use crate::check16::CustomCrc16;
+
 pub use crate::check32::Crc32;
+pub use crate::check32::Crc32BuildHasher;
 pub use crate::check32::Crc32C;
-pub use crate::check32::CustomCrc32;
+pub use crate::check32::Crc32CBuildHasher;
 pub use crate::check32::Crc32Digest;
+pub use crate::check32::CustomCrc32;
 
+pub use crate::check64::Crc64Digest;
 pub use crate::check64::Crc64ECMA;
-pub use crate::check64::Crc64Digest;
\ No newline at end of file
+pub use crate::check64::Crc64EcmaBuildHasher;
+pub use crate::check64::Crc64ISO;
+pub use crate::check64::Crc64IsoBuildHasher;
+pub use crate::check64::CustomCrc64;
+
+pub use crate::crc::{Crc, CrcDigest, CrcWidth};
+
+pub use crate::params::CrcParams;
+
+pub use crate::rolling::{RollingCrc, RollingCrc32, RollingCrc64};
+
+#[cfg(feature = "std")]
+pub use crate::io::{CrcReader, CrcWriter, VerifyingReader};
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub use crate::async_io::{AsyncCrcReader, AsyncCrcWriter};
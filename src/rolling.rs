@@ -0,0 +1,310 @@
+//! Rolling crc over a sliding window, for content defined chunking
+//!
+//! [RollingCrc] keeps the crc of the last `window` bytes. Rolling the window by one byte removes
+//! the outgoing byte through a precomputed table and adds the incoming byte with the regular
+//! table lookup, so every step is O(1) irrespective of the window size. [ChunkBoundaries] uses it
+//! to split data where the crc of the window matches a mask, so the boundaries move along with
+//! the content when bytes are inserted or removed.
+//!
+//! ```
+//! use librscrc::prelude::*;
+//!
+//! let data = b"the quick brown fox jumps over the lazy dog";
+//! let mut rolling = RollingCrc32::new(0x1EDC6F41, 4);
+//! rolling.update(&data[..4]);
+//! for i in 4..data.len() {
+//!     rolling.roll(data[i - 4], data[i]);
+//! }
+//!
+//! let mut crc = Crc32C::new_lookup();
+//! crc.update(b" dog");
+//! assert_eq!(rolling.digest(), crc.digest());
+//! ```
+
+use crate::crc::{Crc, CrcDigest, CrcWidth};
+use crate::params::CrcParams;
+
+/// Rolling crc32 for any polynomial, see [RollingCrc]
+pub type RollingCrc32 = RollingCrc<u32>;
+
+/// Rolling crc64 for any polynomial, see [RollingCrc]
+pub type RollingCrc64 = RollingCrc<u64>;
+
+/// Crc of a window of the last `window` bytes, which rolls one byte at a time in O(1).
+///
+/// The window is filled with [update](CrcDigest::update), after which [roll](Self::roll) moves it
+/// forward. The caller provides the outgoing byte, as it already holds the data.
+#[derive(Clone)]
+pub struct RollingCrc<W: CrcWidth> {
+    crc: Crc<W>,
+    window: usize,
+    out_table: [W; 256],
+}
+
+impl<W: CrcWidth> RollingCrc<W> {
+    /// Creates a new `RollingCrc` over `window` bytes for the reflected polynomial with the
+    /// register initialized to and xored with all ones, like
+    /// [Crc::new_lookup](crate::crc::Crc::new_lookup)
+    ///
+    /// # Panics
+    /// Panics if `window` is zero.
+    pub fn new(polynomial: W, window: usize) -> Self {
+        Self::from_crc(Crc::new_lookup(polynomial), window)
+    }
+
+    /// Creates a new `RollingCrc` over `window` bytes for the given crc parameters.
+    ///
+    /// # Panics
+    /// Panics if `window` is zero or `params.width` does not match the width of `W`.
+    pub fn with_params(params: CrcParams, window: usize) -> Self {
+        Self::from_crc(Crc::new_lookup_with_params(params), window)
+    }
+
+    /// The outgoing byte `b` contributes the difference between the crcs of `b` followed by
+    /// `window` zeros and of `window` zeros alone. The difference also moves the contribution
+    /// of `init` back by the rolled byte, and is the same for the kernel state and the register.
+    fn from_crc(crc: Crc<W>, window: usize) -> Self {
+        assert!(window > 0, "the window must be at least one byte");
+        let combiner = crc.combiner(window as u64);
+        let append_zeros = |state| crc.state_of(W::append_zeros(&combiner, crc.digest_of(state)));
+        let zeros = append_zeros(crc.state);
+
+        let mut out_table = [W::default(); 256];
+        for (b, entry) in out_table.iter_mut().enumerate() {
+            let state = (crc.compute)(&crc, crc.state, &[b as u8]);
+            *entry = append_zeros(state) ^ zeros;
+        }
+
+        Self {
+            crc,
+            window,
+            out_table,
+        }
+    }
+
+    /// Size of the window in bytes
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Resets the crc to its initial state, emptying the window
+    pub fn reset(&mut self) {
+        self.crc.reset();
+    }
+
+    /// Moves the window forward by one byte, removing `outgoing` and adding `incoming`. The
+    /// window must be full, and `outgoing` must be the byte `window` bytes before `incoming`.
+    pub fn roll(&mut self, outgoing: u8, incoming: u8) {
+        let state = (self.crc.compute)(&self.crc, self.crc.state, &[incoming]);
+        self.crc.state = state ^ self.out_table[outgoing as usize];
+    }
+
+    /// Returns an iterator over the end offsets of content defined chunks of `data`, see
+    /// [ChunkBoundaries]
+    pub fn chunk_boundaries<'a>(&self, data: &'a [u8], mask: W) -> ChunkBoundaries<'a, W> {
+        let mut rolling = self.clone();
+        rolling.reset();
+        ChunkBoundaries {
+            rolling,
+            data,
+            mask,
+            position: 0,
+            chunk_start: 0,
+            min_size: self.window,
+            max_size: usize::MAX,
+        }
+    }
+}
+
+impl<W: CrcWidth> CrcDigest for RollingCrc<W> {
+    type Output = W;
+
+    /// Update digest with data, used to fill the window before rolling it
+    fn update(&mut self, data: &[u8]) {
+        self.crc.update(data);
+    }
+
+    /// Returns the crc of the window
+    fn digest(&self) -> W {
+        self.crc.digest()
+    }
+}
+
+/// Iterator over the end offsets of content defined chunks, created by
+/// [RollingCrc::chunk_boundaries].
+///
+/// A chunk ends after a byte when the crc of the window ending at that byte has all of the bits
+/// of `mask` cleared, so a mask with `n` bits set gives chunks of about `2^n` bytes on random
+/// data. Chunks are at least [min_size](Self::min_size) bytes, which defaults to the window
+/// size, and at most [max_size](Self::max_size) bytes. The last offset is the length of the data,
+/// unless the data is empty.
+///
+/// ```
+/// use librscrc::prelude::*;
+///
+/// let data: Vec<u8> = (0..100_000u32).map(|i| (i * 7919 >> 5) as u8).collect();
+/// let rolling = RollingCrc32::new(0x04C11DB7, 48);
+/// let mut start = 0;
+/// for end in rolling.chunk_boundaries(&data, 0xFFF).max_size(16384) {
+///     let chunk = &data[start..end];
+///     assert!(chunk.len() <= 16384);
+///     start = end;
+/// }
+/// assert_eq!(start, data.len());
+/// ```
+#[derive(Clone)]
+pub struct ChunkBoundaries<'a, W: CrcWidth> {
+    rolling: RollingCrc<W>,
+    data: &'a [u8],
+    mask: W,
+    position: usize,
+    chunk_start: usize,
+    min_size: usize,
+    max_size: usize,
+}
+
+impl<'a, W: CrcWidth> ChunkBoundaries<'a, W> {
+    /// Sets the minimum size of a chunk, apart from the last one. Chunks shorter than the window
+    /// are not content defined, so it should be at least the window size.
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Sets the maximum size of a chunk, after which a boundary is forced.
+    ///
+    /// # Panics
+    /// Panics if `max_size` is zero.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        assert!(max_size > 0, "chunks must be at least one byte");
+        self.max_size = max_size;
+        self
+    }
+}
+
+impl<'a, W: CrcWidth> Iterator for ChunkBoundaries<'a, W> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let window = self.rolling.window;
+        while self.position < self.data.len() {
+            let i = self.position;
+            if i < window {
+                self.rolling.update(&self.data[i..i + 1]);
+            } else {
+                self.rolling.roll(self.data[i - window], self.data[i]);
+            }
+            self.position += 1;
+
+            let len = self.position - self.chunk_start;
+            let matches =
+                self.position >= window && self.rolling.digest() & self.mask == W::default();
+            if len >= self.max_size || (len >= self.min_size && matches) {
+                self.chunk_start = self.position;
+                return Some(self.position);
+            }
+        }
+
+        if self.chunk_start < self.data.len() {
+            self.chunk_start = self.data.len();
+            return Some(self.data.len());
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogue::{CRC_16_XMODEM, CRC_32_BZIP2};
+    use crate::test_util::pseudo_random_data;
+
+    fn test_rolling<W: CrcWidth>(rolling: RollingCrc<W>, crc: Crc<W>) {
+        let data = pseudo_random_data(20_000);
+        let window = rolling.window();
+        let mut rolling = rolling;
+        rolling.update(&data[..window]);
+        for i in window..1000 {
+            rolling.roll(data[i - window], data[i]);
+            let mut expected = crc.clone();
+            expected.update(&data[i + 1 - window..=i]);
+            assert_eq!(rolling.digest(), expected.digest());
+        }
+    }
+
+    #[test]
+    fn test_rolling_crc() {
+        for window in [1, 4, 16, 48, 100] {
+            test_rolling(
+                RollingCrc32::new(0x1EDC6F41, window),
+                Crc::new_lookup(0x1EDC6F41),
+            );
+            test_rolling(
+                RollingCrc32::with_params(CRC_32_BZIP2, window),
+                Crc::new_lookup_with_params(CRC_32_BZIP2),
+            );
+            test_rolling(
+                RollingCrc64::new(0x42F0E1EBA9EA3693, window),
+                Crc::new_lookup(0x42F0E1EBA9EA3693),
+            );
+            test_rolling(
+                RollingCrc::<u16>::with_params(CRC_16_XMODEM, window),
+                Crc::new_lookup_with_params(CRC_16_XMODEM),
+            );
+        }
+    }
+
+    #[test]
+    fn test_chunk_boundaries() {
+        let data = pseudo_random_data(20_000);
+        let rolling = RollingCrc64::new(0x42F0E1EBA9EA3693, 32);
+        let boundaries: Vec<usize> = rolling
+            .chunk_boundaries(&data, 0xFF)
+            .min_size(64)
+            .max_size(1024)
+            .collect();
+        assert_eq!(boundaries.last(), Some(&data.len()));
+        assert!(boundaries.len() > 20);
+
+        let mut start = 0;
+        for &end in &boundaries {
+            let len = end - start;
+            assert!(len <= 1024);
+            if end != data.len() {
+                assert!(len >= 64);
+                let mut crc = Crc::<u64>::new_lookup(0x42F0E1EBA9EA3693);
+                crc.update(&data[end - 32..end]);
+                assert!(len == 1024 || crc.digest() & 0xFF == 0);
+            }
+            start = end;
+        }
+
+        // inserting data only moves the boundaries close to the insertion
+        let mut shifted = b"inserted bytes".to_vec();
+        shifted.extend_from_slice(&data);
+        let shifted_boundaries: Vec<usize> = rolling
+            .chunk_boundaries(&shifted, 0xFF)
+            .min_size(64)
+            .map(|end| end - 14)
+            .collect();
+        let unshifted: Vec<usize> = rolling.chunk_boundaries(&data, 0xFF).min_size(64).collect();
+        assert_eq!(
+            shifted_boundaries[shifted_boundaries.len() - 10..],
+            unshifted[unshifted.len() - 10..]
+        );
+
+        assert_eq!(rolling.chunk_boundaries(&[], 0xFF).next(), None);
+        assert_eq!(
+            rolling
+                .chunk_boundaries(&data[..10], 0xFF)
+                .collect::<Vec<_>>(),
+            [10]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_empty_window() {
+        RollingCrc32::new(0x1EDC6F41, 0);
+    }
+}
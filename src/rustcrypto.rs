@@ -0,0 +1,133 @@
+//! Implementations of the RustCrypto `digest` traits
+//!
+//! The output of the crc types is the crc in big endian byte order. [Crc32], [Crc32C],
+//! [Crc64ECMA] and [Crc64ISO] implement [digest::Digest] through their [Default] impl, while the
+//! custom [Crc] types can be used through [digest::DynDigest] or the individual traits.
+
+use digest::consts::{U1, U2, U4, U8};
+use digest::{FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update};
+
+use crate::check16::{
+    Crc16CcittFalse, Crc16Kermit, Crc16Modbus, Crc16X25, Crc16Xmodem, CustomCrc16,
+};
+use crate::check32::{Crc32, Crc32C, CustomCrc32};
+use crate::check64::{Crc64ECMA, Crc64ISO, CustomCrc64};
+use crate::check8::{Crc8Autosar, Crc8Maxim, Crc8Smbus, CustomCrc8};
+use crate::crc::CrcDigest;
+
+macro_rules! impl_digest {
+    ($size:ty, $($ty:ty),+) => {
+        $(
+            impl HashMarker for $ty {}
+
+            impl OutputSizeUser for $ty {
+                type OutputSize = $size;
+            }
+
+            impl Update for $ty {
+                fn update(&mut self, data: &[u8]) {
+                    CrcDigest::update(self, data);
+                }
+            }
+
+            impl FixedOutput for $ty {
+                fn finalize_into(self, out: &mut Output<Self>) {
+                    out.copy_from_slice(&CrcDigest::digest(&self).to_be_bytes());
+                }
+            }
+
+            impl Reset for $ty {
+                fn reset(&mut self) {
+                    <$ty>::reset(self);
+                }
+            }
+
+            impl FixedOutputReset for $ty {
+                fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+                    out.copy_from_slice(&CrcDigest::digest(self).to_be_bytes());
+                    <$ty>::reset(self);
+                }
+            }
+        )+
+    };
+}
+
+impl_digest!(U1, Crc8Smbus, Crc8Maxim, Crc8Autosar, CustomCrc8);
+impl_digest!(
+    U2,
+    Crc16Modbus,
+    Crc16CcittFalse,
+    Crc16Xmodem,
+    Crc16Kermit,
+    Crc16X25,
+    CustomCrc16
+);
+impl_digest!(U4, Crc32, Crc32C, CustomCrc32);
+impl_digest!(U8, Crc64ECMA, Crc64ISO, CustomCrc64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use digest::{Digest, DynDigest};
+
+    const CHECK: &[u8] = b"123456789";
+
+    fn dyn_digest(digest: &mut dyn DynDigest) -> [u8; 8] {
+        let mut out = [0u8; 8];
+        let size = digest.output_size();
+        digest.update(b"1234");
+        digest.update(b"56789");
+        digest.finalize_into_reset(&mut out[..size]).unwrap();
+        // the digest is reset so the same data gives the same output
+        let mut again = [0u8; 8];
+        digest.update(CHECK);
+        digest.finalize_into_reset(&mut again[..size]).unwrap();
+        assert_eq!(out, again);
+        out
+    }
+
+    #[test]
+    fn test_digest() {
+        assert_eq!(
+            <Crc32 as Digest>::digest(CHECK)[..],
+            0xCBF43926u32.to_be_bytes()
+        );
+        assert_eq!(
+            <Crc32C as Digest>::digest(CHECK)[..],
+            0xE3069283u32.to_be_bytes()
+        );
+        assert_eq!(
+            <Crc64ECMA as Digest>::digest(CHECK)[..],
+            0x995DC9BBDF1939FAu64.to_be_bytes()
+        );
+        assert_eq!(
+            <Crc64ISO as Digest>::digest(CHECK)[..],
+            0xB90956C775A41001u64.to_be_bytes()
+        );
+
+        let mut crc = <Crc32C as Digest>::new();
+        Digest::update(&mut crc, b"1234");
+        let crc = crc.chain_update(b"56789");
+        assert_eq!(crc.finalize()[..], 0xE3069283u32.to_be_bytes());
+    }
+
+    #[test]
+    fn test_dyn_digest() {
+        assert_eq!(
+            dyn_digest(&mut Crc32::new_lookup())[..4],
+            0xCBF43926u32.to_be_bytes()
+        );
+        assert_eq!(
+            dyn_digest(&mut CustomCrc32::new_lookup(0x1EDC6F41))[..4],
+            0xE3069283u32.to_be_bytes()
+        );
+        assert_eq!(
+            dyn_digest(&mut Crc64ISO::new_lookup()),
+            0xB90956C775A41001u64.to_be_bytes()
+        );
+        assert_eq!(
+            dyn_digest(&mut CustomCrc64::new_lookup(0x42F0E1EBA9EA3693)),
+            0x995DC9BBDF1939FAu64.to_be_bytes()
+        );
+    }
+}
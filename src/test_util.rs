@@ -0,0 +1,153 @@
+//! Helpers shared by the unit tests
+
+use core::fmt::Debug;
+use core::ops::Not;
+
+use crate::crc::CrcDigest;
+
+pub(crate) static LARGE_DATA_1: &[u8; 144] = include_bytes!("../sample_files/test_data.txt");
+pub(crate) static LARGE_DATA_2: &[u8; 241] =
+    include_bytes!("../sample_files/test_data_odd_size.txt");
+
+/// Returns `len` bytes of deterministic pseudo random data, e.g. for buffers longer than the
+/// sample files
+pub(crate) fn pseudo_random_data(len: usize) -> Vec<u8> {
+    (0..len as u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+        .collect()
+}
+
+/// Returns the digest of `crc` after updating it with `data`
+pub(crate) fn checksum<D: CrcDigest>(mut crc: D, data: &[u8]) -> D::Output {
+    crc.update(data);
+    crc.digest()
+}
+
+/// Checks `update_zeros` of `crc` against updating with zeros, and that a terabyte of zeros
+/// only takes a few multiplications
+pub(crate) fn assert_update_zeros<D>(crc: D)
+where
+    D: CrcDigest + Clone,
+    D::Output: PartialEq + Debug,
+{
+    let zeros = [0u8; 5000];
+    for len in [0, 1, 7, 16, 241, 5000] {
+        let mut expected = crc.clone();
+        expected.update(LARGE_DATA_2);
+        expected.update(&zeros[..len]);
+        expected.update(b"123456789");
+
+        let mut crc = crc.clone();
+        crc.update(LARGE_DATA_2);
+        crc.update_zeros(len as u64);
+        crc.update(b"123456789");
+        assert_eq!(crc.digest(), expected.digest());
+    }
+
+    let mut expected = crc.clone();
+    expected.update_zeros(1 << 42);
+    let mut crc = crc;
+    crc.update_zeros(1 << 40);
+    crc.update_zeros(3 << 40);
+    assert_eq!(crc.digest(), expected.digest());
+}
+
+/// Checks `patch` of `crc` against checksumming the edited data, for edits at the start, in
+/// the middle and at the end of the data, where no bytes follow the edit
+pub(crate) fn assert_patch<D>(
+    crc: &D,
+    patch: impl Fn(D::Output, u64, u64, &[u8], &[u8]) -> D::Output,
+) where
+    D: CrcDigest + Clone,
+    D::Output: Copy + PartialEq + Debug,
+{
+    let old_data = [LARGE_DATA_2, &LARGE_DATA_2[..]].concat();
+    let old_crc = checksum(crc.clone(), &old_data);
+    for (offset, len) in [(0, 1), (0, 16), (100, 9), (300, 182), (481, 1), (0, 482)] {
+        let mut new_data = old_data.clone();
+        for (i, b) in new_data[offset..offset + len].iter_mut().enumerate() {
+            *b = b.wrapping_mul(31) ^ i as u8 ^ 0x5A;
+        }
+        let old = &old_data[offset..offset + len];
+        let new = &new_data[offset..offset + len];
+        let total_len = old_data.len() as u64;
+        assert_eq!(
+            patch(old_crc, total_len, offset as u64, old, new),
+            checksum(crc.clone(), &new_data)
+        );
+    }
+}
+
+/// Checks `forge` of `crc` by forging bytes at several offsets of the data, each time targeting
+/// a crc which differs from the one of the data before the offset
+pub(crate) fn assert_forge<D, B>(crc: &D, forge: impl Fn(&[u8], usize, D::Output) -> B)
+where
+    D: CrcDigest + Clone,
+    D::Output: Not<Output = D::Output> + Copy + PartialEq + Debug,
+    B: AsRef<[u8]>,
+{
+    let mut data = *LARGE_DATA_2;
+    for offset in [0, 50, 140, data.len() - 8] {
+        let target = !checksum(crc.clone(), &data[..offset]);
+        let bytes = forge(&data, offset, target);
+        let bytes = bytes.as_ref();
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        assert_eq!(checksum(crc.clone(), &data), target);
+    }
+}
+
+/// Tests a crc type defined with `fixed_crc!` against the naive approach of its custom
+/// counterpart with the same parameters, for every supported backend and for the combine,
+/// patch, update_zeros and const checksum helpers.
+macro_rules! test_fixed_crc {
+    ($test:ident, $ty:ty, $custom:ty, $params:expr) => {
+        #[test]
+        fn $test() {
+            use $crate::backend::Backend;
+            use $crate::crc::CrcDigest;
+            use $crate::test_util::{checksum, pseudo_random_data, LARGE_DATA_1, LARGE_DATA_2};
+
+            let params = $params;
+            let data = pseudo_random_data(5000);
+            let expected = |data: &[u8]| checksum(<$custom>::new_naive_with_params(params), data);
+            assert_eq!(<$ty>::supported_backends().next(), Some(Backend::Naive));
+            for backend in <$ty>::supported_backends() {
+                let crc = <$ty>::new_with_backend(backend).unwrap();
+                assert_eq!(crc.selected_backend(), backend);
+                assert_eq!(u64::from(checksum(crc.clone(), b"123456789")), params.check);
+                let patched = crc.patch(params.check as _, 9, 2, b"34", b"43");
+                assert_eq!(patched, expected(b"124356789"));
+                assert_eq!(checksum(crc.clone(), LARGE_DATA_1), expected(LARGE_DATA_1));
+                for len in [0, 100, 127, 128, 255, 256, 257, 1000, 4099, 5000] {
+                    assert_eq!(checksum(crc.clone(), &data[..len]), expected(&data[..len]));
+                }
+
+                let mut crc = crc;
+                crc.update(&data[..300]);
+                crc.reset();
+                crc.update(&LARGE_DATA_2[..7]);
+                crc.update(&LARGE_DATA_2[7..]);
+                assert_eq!(crc.digest(), expected(LARGE_DATA_2));
+            }
+
+            assert_eq!(<$ty>::const_checksum(&data), expected(&data));
+
+            let mut crc = <$ty>::new_lookup();
+            crc.update(&data[..10]);
+            crc.update_zeros(1000);
+            let mut zeros = data[..10].to_vec();
+            zeros.resize(1010, 0);
+            assert_eq!(crc.digest(), expected(&zeros));
+
+            let (a, b) = LARGE_DATA_2.split_at(100);
+            let crc_a = checksum(<$ty>::new_lookup(), a);
+            let crc_b = checksum(<$ty>::new_lookup(), b);
+            assert_eq!(
+                <$ty>::combine(crc_a, crc_b, b.len() as u64),
+                expected(LARGE_DATA_2)
+            );
+        }
+    };
+}
+
+pub(crate) use test_fixed_crc;